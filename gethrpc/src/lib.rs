@@ -85,6 +85,10 @@ pub struct RPCBlock {
     pub gasUsed: String,
     pub timestamp: String,
     #[serde(default)]
+    pub baseFeePerGas: Option<String>,
+    #[serde(default)]
+    pub excessBlobGas: Option<String>,
+    #[serde(default)]
     pub transactions: Vec<String>,
     pub uncles: Vec<String>,
 }
@@ -33,6 +33,8 @@ impl JSONBlock {
             number: self.number,
             difficulty: self.difficulty,
             gas_limit: self.gas_limit,
+            base_fee: Gas::zero(),
+            excess_blob_gas: Gas::zero(),
         }
     }
 
@@ -260,5 +262,7 @@ pub fn create_context(v: &Value) -> Context {
         value: value,
         apprent_value: value,
         is_system: false,
+        is_static: false,
+        blob_hashes: Vec::new(),
     }
 }
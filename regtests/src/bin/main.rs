@@ -20,7 +20,9 @@ use bigint::{Gas, Address, U256, M256, H256};
 use hexutil::*;
 use sputnikvm::{HeaderParams, Context, SeqTransactionVM, ValidTransaction, VM, Log, Patch,
                 AccountCommitment, AccountChange, FrontierPatch, HomesteadPatch,
-                EIP150Patch, EIP160Patch};
+                EIP150Patch, EIP160Patch, SpuriousDragonPatch, ByzantiumPatch,
+                ConstantinoplePatch, IstanbulPatch, BerlinPatch, LondonPatch,
+                ShanghaiPatch, CancunPatch};
 use sputnikvm::errors::RequireError;
 use gethrpc::{GethRPCClient, NormalGethRPCClient, RecordGethRPCClient, CachedGethRPCClient, RPCCall, RPCBlock, RPCTransaction, RPCLog};
 
@@ -31,6 +33,12 @@ fn from_rpc_block(block: &RPCBlock) -> HeaderParams {
         number: U256::from_str(&block.number).unwrap(),
         difficulty: U256::from_str(&block.difficulty).unwrap(),
         gas_limit: Gas::from_str(&block.gasLimit).unwrap(),
+        base_fee: block.baseFeePerGas.as_ref()
+            .map(|base_fee| Gas::from_str(base_fee).unwrap())
+            .unwrap_or(Gas::zero()),
+        excess_blob_gas: block.excessBlobGas.as_ref()
+            .map(|excess_blob_gas| Gas::from_str(excess_blob_gas).unwrap())
+            .unwrap_or(Gas::zero()),
     }
 }
 
@@ -47,6 +55,10 @@ fn from_rpc_transaction(transaction: &RPCTransaction) -> ValidTransaction {
         gas_price: Gas::from_str(&transaction.gasPrice).unwrap(),
         input: read_hex(&transaction.input).unwrap(),
         nonce: U256::from_str(&transaction.nonce).unwrap(),
+        access_list: Vec::new(),
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: Vec::new(),
     }
 }
 
@@ -245,6 +257,14 @@ fn test_blocks_patch<T: GethRPCClient>(client: &mut T, number: &str, patch: Opti
         Some("homestead") => test_blocks::<_, HomesteadPatch>(client, number),
         Some("eip150") => test_blocks::<_, EIP150Patch>(client, number),
         Some("eip160") => test_blocks::<_, EIP160Patch>(client, number),
+        Some("spuriousdragon") => test_blocks::<_, SpuriousDragonPatch>(client, number),
+        Some("byzantium") => test_blocks::<_, ByzantiumPatch>(client, number),
+        Some("constantinople") => test_blocks::<_, ConstantinoplePatch>(client, number),
+        Some("istanbul") => test_blocks::<_, IstanbulPatch>(client, number),
+        Some("berlin") => test_blocks::<_, BerlinPatch>(client, number),
+        Some("london") => test_blocks::<_, LondonPatch>(client, number),
+        Some("shanghai") => test_blocks::<_, ShanghaiPatch>(client, number),
+        Some("cancun") => test_blocks::<_, CancunPatch>(client, number),
         _ => panic!("Unknown patch."),
     }
 }
@@ -2,7 +2,8 @@
 
 use std::collections::hash_set::HashSet;
 use std::collections::hash_map::{self, HashMap};
-use bigint::{M256, U256, Address};
+use bigint::{M256, U256, H256, Address};
+use sha3::{Digest, Keccak256};
 
 use errors::{RequireError, CommitError};
 
@@ -13,6 +14,7 @@ pub struct Storage {
     partial: bool,
     address: Address,
     storage: HashMap<U256, M256>,
+    original: HashMap<U256, M256>,
 }
 
 impl Into<HashMap<U256, M256>> for Storage {
@@ -28,6 +30,7 @@ impl Storage {
             partial: partial,
             address: address,
             storage: HashMap::new(),
+            original: HashMap::new(),
         }
     }
 
@@ -62,14 +65,33 @@ impl Storage {
         }
     }
 
+    /// Read the original value from the storage, which is the value
+    /// before the current transaction modified it.
+    pub fn original(&self, index: U256) -> Result<M256, RequireError> {
+        match self.original.get(&index) {
+            Some(&v) => Ok(v),
+            None => self.read(index),
+        }
+    }
+
     /// Write a value into the storage.
     pub fn write(&mut self, index: U256, value: M256) -> Result<(), RequireError> {
         if !self.storage.contains_key(&index) && self.partial {
             return Err(RequireError::AccountStorage(self.address, index));
         }
+        if !self.original.contains_key(&index) {
+            let original = self.read(index)?;
+            self.original.insert(index, original);
+        }
         self.storage.insert(index, value);
         Ok(())
     }
+
+    /// Make the current values the original values, as at the start
+    /// of a new transaction.
+    fn reset_original(&mut self) {
+        self.original = HashMap::new();
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -192,7 +214,10 @@ impl AccountChange {
 pub struct AccountState {
     accounts: HashMap<Address, AccountChange>,
     codes: HashMap<Address, Vec<u8>>,
+    code_hashes: HashMap<Address, H256>,
     premarked_exists: HashSet<Address>,
+    accessed_addresses: HashSet<Address>,
+    accessed_storage_keys: HashSet<(Address, U256)>,
 }
 
 impl Default for AccountState {
@@ -200,7 +225,10 @@ impl Default for AccountState {
         Self {
             accounts: HashMap::new(),
             codes: HashMap::new(),
+            code_hashes: HashMap::new(),
             premarked_exists: HashSet::new(),
+            accessed_addresses: HashSet::new(),
+            accessed_storage_keys: HashSet::new(),
         }
     }
 }
@@ -358,6 +386,50 @@ impl AccountState {
         Ok(())
     }
 
+    /// Make all current storage values the original values. This
+    /// should be called at the start of a new transaction.
+    pub fn reset_original_storage(&mut self) {
+        for account in self.accounts.values_mut() {
+            match account {
+                &mut AccountChange::Full { ref mut changing_storage, .. } => {
+                    changing_storage.reset_original();
+                },
+                &mut AccountChange::Create { ref mut storage, .. } => {
+                    storage.reset_original();
+                },
+                _ => (),
+            }
+        }
+    }
+
+    /// Forget all accessed addresses and storage keys. This should
+    /// be called at the start of a new transaction.
+    pub fn clear_accessed(&mut self) {
+        self.accessed_addresses.clear();
+        self.accessed_storage_keys.clear();
+    }
+
+    /// Mark an address as accessed (EIP-2929).
+    pub fn access_address(&mut self, address: Address) {
+        self.accessed_addresses.insert(address);
+    }
+
+    /// Mark a storage key as accessed (EIP-2929).
+    pub fn access_storage(&mut self, address: Address, index: U256) {
+        self.accessed_storage_keys.insert((address, index));
+    }
+
+    /// Whether the address has been accessed in this transaction.
+    pub fn is_address_accessed(&self, address: Address) -> bool {
+        self.accessed_addresses.contains(&address)
+    }
+
+    /// Whether the storage key has been accessed in this
+    /// transaction.
+    pub fn is_storage_accessed(&self, address: Address, index: U256) -> bool {
+        self.accessed_storage_keys.contains(&(address, index))
+    }
+
     /// Test whether an account at given address is considered
     /// existing.
     pub fn exists(&self, address: Address) -> Result<bool, RequireError> {
@@ -368,6 +440,19 @@ impl AccountState {
         }
     }
 
+    /// Test whether an account at given address is empty, i.e. it
+    /// has zero nonce, zero balance and no code. A non-existing
+    /// account is also considered empty.
+    pub fn is_empty(&self, address: Address) -> Result<bool, RequireError> {
+        match self.accounts.get(&address) {
+            Some(&AccountChange::Create { nonce, balance, ref code, .. }) |
+            Some(&AccountChange::Full { nonce, balance, ref code, .. }) => {
+                Ok(nonce == U256::zero() && balance == U256::zero() && code.len() == 0)
+            },
+            _ => Err(RequireError::Account(address)),
+        }
+    }
+
     /// Premark an address as exist.
     pub fn premark_exists(&mut self, address: Address) {
         match self.accounts.get_mut(&address) {
@@ -406,6 +491,19 @@ impl AccountState {
         return Err(RequireError::AccountCode(address));
     }
 
+    /// Find the hash of code by its address in this account state,
+    /// which is cached after the first lookup. If the search failed,
+    /// returns a `RequireError`.
+    pub fn code_hash(&mut self, address: Address) -> Result<H256, RequireError> {
+        if let Some(hash) = self.code_hashes.get(&address) {
+            return Ok(*hash);
+        }
+
+        let hash = H256::from(Keccak256::digest(self.code(address)?).as_slice());
+        self.code_hashes.insert(address, hash);
+        Ok(hash)
+    }
+
     /// Find nonce by its address in this account state. If the search
     /// failed, returns a `RequireError`.
     pub fn nonce(&self, address: Address) -> Result<U256, RequireError> {
@@ -514,12 +612,14 @@ impl AccountState {
         };
 
         self.accounts.insert(address, account);
+        self.code_hashes.remove(&address);
 
         Ok(())
     }
 
     /// Deposit code in to a created account.
     pub fn code_deposit(&mut self, address: Address, new_code: &[u8]) {
+        self.code_hashes.remove(&address);
         match self.accounts.get_mut(&address).unwrap() {
             &mut AccountChange::Create { ref mut code, ref mut exists, .. } => {
                 *exists = true;
@@ -674,6 +774,7 @@ impl AccountState {
     /// `RequireError`.
     pub fn remove(&mut self, address: Address) -> Result<(), RequireError> {
         self.codes.remove(&address);
+        self.code_hashes.remove(&address);
         self.premarked_exists.remove(&address);
         let account = match self.accounts.remove(&address) {
             Some(AccountChange::Full {
@@ -7,12 +7,33 @@ use bigint::{Address, U256};
 pub enum PreExecutionError {
     /// The caller is invalid.
     InvalidCaller,
+    /// The signature is replay-protected for a different chain, or
+    /// the patch does not accept replay-protected signatures.
+    InvalidChainId,
     /// Nonce of the caller does not equal.
     InvalidNonce,
     /// Balance from the caller is insufficient.
     InsufficientBalance,
     /// Gas limit is smaller than the intrinsic gas required.
     InsufficientGasLimit,
+    /// The transaction type is not supported by the patch.
+    UnsupportedTransactionType,
+    /// Maximum fee per gas is lower than the base fee of the block.
+    FeeCapBelowBaseFee,
+    /// Maximum priority fee per gas is higher than the maximum fee
+    /// per gas.
+    PriorityFeeAboveFeeCap,
+    /// The init code of a contract creation transaction exceeds the
+    /// maximum init code size of the patch.
+    InitCodeSizeLimit,
+    /// A blob transaction has no blob versioned hashes, or one of
+    /// them has an unknown version.
+    InvalidBlobVersionedHashes,
+    /// A blob transaction uses more blob gas than allowed in a block.
+    TooManyBlobs,
+    /// Maximum fee per blob gas is lower than the blob base fee of
+    /// the block.
+    BlobFeeCapBelowBlobBaseFee,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +57,23 @@ pub enum OnChainError {
     /// For instruction that requires reading a range, it is
     /// invalid. This in the Yellow Paper is covered by EmptyGas.
     InvalidRange,
+    /// The code to be deposited by a contract creation exceeds the
+    /// maximum code size of the patch.
+    CodeSizeLimit,
+    /// The init code of CREATE or CREATE2 exceeds the maximum init
+    /// code size of the patch.
+    InitCodeSizeLimit,
+    /// The code to be deposited by a contract creation starts with
+    /// the reserved 0xEF byte.
+    InvalidCodePrefix,
+    /// RETURNDATACOPY tries to read beyond the end of the return
+    /// data buffer.
+    ReturnDataOutOfBounds,
+    /// An instruction tries to modify the state in a static call
+    /// frame.
+    StaticStateChange,
+    /// The input of a precompiled contract is invalid.
+    InvalidPrecompiledInput,
 }
 
 impl From<OnChainError> for RuntimeError {
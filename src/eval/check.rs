@@ -2,16 +2,36 @@
 
 use bigint::{U256, M256, Gas};
 
-use ::{Memory, Instruction, Patch};
+use ::{Memory, Instruction, Patch, SstoreGasMetering};
 use errors::{OnChainError, NotSupportedError, EvalOnChainError};
 use eval::{State, ControlCheck};
 
 use super::util::check_range;
 
+const G_SSTORE_SENTRY: usize = 2300;
+
 #[allow(unused_variables)]
 pub fn extra_check_opcode<M: Memory + Default, P: Patch>(instruction: Instruction, state: &State<M>, stipend_gas: Gas, after_gas: Gas) -> Result<(), OnChainError> {
     match instruction {
-        Instruction::CALL | Instruction::CALLCODE | Instruction::DELEGATECALL => {
+        Instruction::SSTORE => {
+            if P::sstore_gas_metering() == SstoreGasMetering::EIP2200 &&
+                state.available_gas() <= Gas::from(G_SSTORE_SENTRY)
+            {
+                Err(OnChainError::EmptyGas)
+            } else {
+                Ok(())
+            }
+        },
+        Instruction::CREATE | Instruction::CREATE2 => {
+            match P::max_initcode_size() {
+                Some(limit) if state.stack.peek(2).unwrap() > M256::from(limit) => {
+                    Err(OnChainError::InitCodeSizeLimit)
+                },
+                _ => Ok(()),
+            }
+        },
+        Instruction::CALL | Instruction::CALLCODE | Instruction::DELEGATECALL |
+        Instruction::STATICCALL => {
             if P::err_on_call_with_more_gas() && after_gas < state.stack.peek(0).unwrap().into() {
                 Err(OnChainError::EmptyGas)
             } else {
@@ -32,7 +52,7 @@ pub fn check_support<M: Memory + Default>(instruction: Instruction, state: &Stat
             state.memory.check_write(state.stack.peek(0).unwrap().into())?;
             Ok(())
         },
-        Instruction::CALLDATACOPY => {
+        Instruction::CALLDATACOPY | Instruction::RETURNDATACOPY => {
             state.memory.check_write_range(
                 state.stack.peek(0).unwrap().into(), state.stack.peek(2).unwrap().into())?;
             Ok(())
@@ -42,6 +62,13 @@ pub fn check_support<M: Memory + Default>(instruction: Instruction, state: &Stat
                 state.stack.peek(0).unwrap().into(), state.stack.peek(2).unwrap().into())?;
             Ok(())
         },
+        Instruction::MCOPY => {
+            state.memory.check_write_range(
+                state.stack.peek(0).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            state.memory.check_write_range(
+                state.stack.peek(1).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            Ok(())
+        },
         Instruction::EXTCODECOPY => {
             state.memory.check_write_range(
                 state.stack.peek(1).unwrap().into(), state.stack.peek(3).unwrap().into())?;
@@ -57,7 +84,7 @@ pub fn check_support<M: Memory + Default>(instruction: Instruction, state: &Stat
                 state.stack.peek(5).unwrap().into(), state.stack.peek(6).unwrap().into())?;
             Ok(())
         },
-        Instruction::DELEGATECALL => {
+        Instruction::DELEGATECALL | Instruction::STATICCALL => {
             state.memory.check_write_range(
                 state.stack.peek(4).unwrap().into(), state.stack.peek(5).unwrap().into())?;
             Ok(())
@@ -95,6 +122,9 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
         Instruction::XOR => { state.stack.check_pop_push(2, 1)?; Ok(None) },
         Instruction::NOT => { state.stack.check_pop_push(1, 1)?; Ok(None) },
         Instruction::BYTE => { state.stack.check_pop_push(2, 1)?; Ok(None) },
+        Instruction::SHL => { state.stack.check_pop_push(2, 1)?; Ok(None) },
+        Instruction::SHR => { state.stack.check_pop_push(2, 1)?; Ok(None) },
+        Instruction::SAR => { state.stack.check_pop_push(2, 1)?; Ok(None) },
 
         Instruction::SHA3 => {
             state.stack.check_pop_push(2, 1)?;
@@ -136,6 +166,28 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
             check_range(state.stack.peek(1).unwrap().into(), state.stack.peek(3).unwrap().into())?;
             Ok(None)
         },
+        Instruction::EXTCODEHASH => {
+            state.stack.check_pop_push(1, 1)?;
+            let address = state.stack.peek(0).unwrap().into();
+            state.account_state.require_code(address)?;
+            // Accounts with empty code can either be non-existing or
+            // empty, which only the full account tells.
+            if state.account_state.code(address)?.is_empty() {
+                state.account_state.require(address)?;
+            }
+            Ok(None)
+        },
+        Instruction::RETURNDATASIZE => { state.stack.check_pop_push(0, 1)?; Ok(None) },
+        Instruction::RETURNDATACOPY => {
+            state.stack.check_pop_push(3, 0)?;
+            check_range(state.stack.peek(0).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            let data_start = state.stack.peek(1).unwrap();
+            let data_end = data_start + state.stack.peek(2).unwrap();
+            if data_end < data_start || data_end > M256::from(state.ret.len()) {
+                return Err(OnChainError::ReturnDataOutOfBounds.into());
+            }
+            Ok(None)
+        },
 
         Instruction::BLOCKHASH => {
             state.stack.check_pop_push(1, 1)?;
@@ -151,6 +203,11 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
         Instruction::NUMBER => { state.stack.check_pop_push(0, 1)?; Ok(None) },
         Instruction::DIFFICULTY => { state.stack.check_pop_push(0, 1)?; Ok(None) },
         Instruction::GASLIMIT => { state.stack.check_pop_push(0, 1)?; Ok(None) },
+        Instruction::CHAINID => { state.stack.check_pop_push(0, 1)?; Ok(None) },
+        Instruction::BASEFEE => { state.stack.check_pop_push(0, 1)?; Ok(None) },
+        Instruction::BLOBHASH => { state.stack.check_pop_push(1, 1)?; Ok(None) },
+        Instruction::BLOBBASEFEE => { state.stack.check_pop_push(0, 1)?; Ok(None) },
+        Instruction::SELFBALANCE => { state.stack.check_pop_push(0, 1)?; Ok(None) },
 
         Instruction::POP => { state.stack.check_pop_push(1, 0)?; Ok(None) },
         Instruction::MLOAD => { state.stack.check_pop_push(1, 1)?; Ok(None) },
@@ -170,10 +227,30 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
         },
         Instruction::SSTORE => {
             state.stack.check_pop_push(2, 0)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
             state.account_state.require(state.context.address)?;
             state.account_state.require_storage(state.context.address, state.stack.peek(0).unwrap().into())?;
             Ok(None)
         },
+        Instruction::TLOAD => {
+            state.stack.check_pop_push(1, 1)?;
+            Ok(None)
+        },
+        Instruction::TSTORE => {
+            state.stack.check_pop_push(2, 0)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
+            Ok(None)
+        },
+        Instruction::MCOPY => {
+            state.stack.check_pop_push(3, 0)?;
+            check_range(state.stack.peek(0).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            check_range(state.stack.peek(1).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            Ok(None)
+        },
         Instruction::JUMP => {
             state.stack.check_pop_push(1, 0)?;
             Ok(Some(ControlCheck::Jump(state.stack.peek(0).unwrap())))
@@ -190,6 +267,7 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
         Instruction::MSIZE => { state.stack.check_pop_push(0, 1)?; Ok(None) },
         Instruction::GAS => { state.stack.check_pop_push(0, 1)?; Ok(None) },
         Instruction::JUMPDEST => Ok(None),
+        Instruction::PUSH0 => { state.stack.check_pop_push(0, 1)?; Ok(None) },
 
         Instruction::PUSH(v) => { state.stack.check_pop_push(0, 1)?; Ok(None) },
 
@@ -198,17 +276,35 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
 
         Instruction::LOG(v) => {
             state.stack.check_pop_push(v+2, 0)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
             check_range(state.stack.peek(0).unwrap().into(), state.stack.peek(1).unwrap().into())?;
             Ok(None)
         },
         Instruction::CREATE => {
             state.stack.check_pop_push(3, 1)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
+            check_range(state.stack.peek(1).unwrap().into(), state.stack.peek(2).unwrap().into())?;
+            state.account_state.require(state.context.address)?;
+            Ok(None)
+        },
+        Instruction::CREATE2 => {
+            state.stack.check_pop_push(4, 1)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
             check_range(state.stack.peek(1).unwrap().into(), state.stack.peek(2).unwrap().into())?;
             state.account_state.require(state.context.address)?;
             Ok(None)
         },
         Instruction::CALL => {
             state.stack.check_pop_push(7, 1)?;
+            if state.context.is_static && state.stack.peek(2).unwrap() != M256::zero() {
+                return Err(OnChainError::StaticStateChange.into());
+            }
             check_range(state.stack.peek(3).unwrap().into(), state.stack.peek(4).unwrap().into())?;
             check_range(state.stack.peek(5).unwrap().into(), state.stack.peek(6).unwrap().into())?;
             state.account_state.require(state.context.address)?;
@@ -223,12 +319,12 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
             state.account_state.require(state.stack.peek(1).unwrap().into())?;
             Ok(None)
         },
-        Instruction::RETURN => {
+        Instruction::RETURN | Instruction::REVERT => {
             state.stack.check_pop_push(2, 0)?;
             check_range(state.stack.peek(0).unwrap().into(), state.stack.peek(1).unwrap().into())?;
             Ok(None)
         },
-        Instruction::DELEGATECALL => {
+        Instruction::DELEGATECALL | Instruction::STATICCALL => {
             state.stack.check_pop_push(6, 1)?;
             check_range(state.stack.peek(2).unwrap().into(), state.stack.peek(3).unwrap().into())?;
             check_range(state.stack.peek(4).unwrap().into(), state.stack.peek(5).unwrap().into())?;
@@ -238,6 +334,9 @@ pub fn check_opcode<M: Memory + Default>(instruction: Instruction, state: &State
         },
         Instruction::SUICIDE => {
             state.stack.check_pop_push(1, 0)?;
+            if state.context.is_static {
+                return Err(OnChainError::StaticStateChange.into());
+            }
             state.account_state.require(state.context.address)?;
             state.account_state.require(state.stack.peek(0).unwrap().into())?;
             Ok(None)
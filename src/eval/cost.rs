@@ -3,7 +3,7 @@
 use bigint::{M256, U256, Gas, Address};
 
 use std::cmp::max;
-use ::{Memory, Instruction, Patch};
+use ::{Memory, Instruction, Patch, SstoreGasMetering};
 use super::State;
 
 const G_ZERO: usize = 0;
@@ -15,8 +15,6 @@ const G_HIGH: usize = 10;
 const G_JUMPDEST: usize = 1;
 const G_SSET: usize = 20000;
 const G_SRESET: usize = 5000;
-const R_SCLEAR: usize = 15000;
-const R_SUICIDE: usize = 24000;
 const G_CREATE: usize = 32000;
 const G_CODEDEPOSIT: usize = 200;
 const G_CALLVALUE: usize = 9000;
@@ -32,20 +30,129 @@ const G_SHA3WORD: usize = 6;
 const G_COPY: usize = 3;
 const G_BLOCKHASH: usize = 20;
 
-fn sstore_cost<M: Memory + Default>(machine: &State<M>) -> Gas {
+const G_SSTORE_NOOP_EIP1283: usize = 200;
+const G_COLD_SLOAD: usize = 2100;
+const G_WARM_STORAGE_READ: usize = 100;
+const G_COLD_ACCOUNT_ACCESS: usize = 2600;
+
+fn is_cold_address<M: Memory + Default, P: Patch>(machine: &State<M>, address: Address) -> bool {
+    P::has_access_tracking() && !machine.account_state.is_address_accessed(address)
+}
+
+fn is_cold_storage<M: Memory + Default, P: Patch>(machine: &State<M>, index: U256) -> bool {
+    P::has_access_tracking() &&
+        !machine.account_state.is_storage_accessed(machine.context.address, index)
+}
+
+fn account_access_cost<M: Memory + Default, P: Patch>(machine: &State<M>, address: Address, warm: Gas) -> Gas {
+    if is_cold_address::<M, P>(machine, address) {
+        G_COLD_ACCOUNT_ACCESS.into()
+    } else {
+        warm
+    }
+}
+
+fn sload_cost<M: Memory + Default, P: Patch>(machine: &State<M>) -> Gas {
+    let index: U256 = machine.stack.peek(0).unwrap().into();
+    if is_cold_storage::<M, P>(machine, index) {
+        G_COLD_SLOAD.into()
+    } else {
+        P::gas_sload()
+    }
+}
+
+fn sstore_reset_cost<P: Patch>() -> Gas {
+    if P::has_access_tracking() {
+        (G_SRESET - G_COLD_SLOAD).into()
+    } else {
+        G_SRESET.into()
+    }
+}
+
+fn sstore_noop_cost<P: Patch>() -> Gas {
+    match P::sstore_gas_metering() {
+        SstoreGasMetering::EIP2200 => P::gas_sload(),
+        _ => G_SSTORE_NOOP_EIP1283.into(),
+    }
+}
+
+fn sstore_cost<M: Memory + Default, P: Patch>(machine: &State<M>) -> Gas {
     let index: U256 = machine.stack.peek(0).unwrap().into();
     let value = machine.stack.peek(1).unwrap();
     let address = machine.context.address;
+    let storage = machine.account_state.storage(address).unwrap();
+    let current = storage.read(index).unwrap();
 
-    if value != M256::zero() && machine.account_state.storage(address).unwrap().read(index).unwrap() == M256::zero() {
+    if P::sstore_gas_metering() == SstoreGasMetering::Legacy {
+        return if value != M256::zero() && current == M256::zero() {
+            G_SSET.into()
+        } else {
+            G_SRESET.into()
+        };
+    }
+
+    let cold = if is_cold_storage::<M, P>(machine, index) {
+        G_COLD_SLOAD.into()
+    } else {
+        Gas::zero()
+    };
+    let original = storage.original(index).unwrap();
+    cold + if current == value || original != current {
+        sstore_noop_cost::<P>()
+    } else if original == M256::zero() {
         G_SSET.into()
     } else {
-        G_SRESET.into()
+        sstore_reset_cost::<P>()
+    }
+}
+
+fn sstore_refund<M: Memory + Default, P: Patch>(machine: &State<M>) -> isize {
+    let index: U256 = machine.stack.peek(0).unwrap().into();
+    let value = machine.stack.peek(1).unwrap();
+    let address = machine.context.address;
+    let storage = machine.account_state.storage(address).unwrap();
+    let current = storage.read(index).unwrap();
+    let sclear = P::gas_refund_sstore_clears().as_u64() as isize;
+
+    if P::sstore_gas_metering() == SstoreGasMetering::Legacy {
+        return if value == M256::zero() && current != M256::zero() {
+            sclear
+        } else {
+            0
+        };
+    }
+
+    let original = storage.original(index).unwrap();
+    if current == value {
+        return 0;
+    }
+    if original == current {
+        return if value == M256::zero() { sclear } else { 0 };
+    }
+
+    let mut refund = 0;
+    if original != M256::zero() {
+        if current == M256::zero() {
+            refund -= sclear;
+        } else if value == M256::zero() {
+            refund += sclear;
+        }
+    }
+    if original == value {
+        let noop: u64 = sstore_noop_cost::<P>().as_u64();
+        if original == M256::zero() {
+            refund += G_SSET as isize - noop as isize;
+        } else {
+            refund += sstore_reset_cost::<P>().as_u64() as isize - noop as isize;
+        }
     }
+    refund
 }
 
 fn call_cost<M: Memory + Default, P: Patch>(machine: &State<M>, instruction: &Instruction) -> Gas {
-    Gas::from(P::gas_call()) + xfer_cost(machine, instruction) + new_cost(machine, instruction)
+    let address: Address = machine.stack.peek(1).unwrap().into();
+    account_access_cost::<M, P>(machine, address, P::gas_call()) +
+        xfer_cost(machine, instruction) + new_cost::<M, P>(machine, instruction)
 }
 
 fn xfer_cost<M: Memory + Default>(machine: &State<M>, instruction: &Instruction) -> Gas {
@@ -61,9 +168,20 @@ fn xfer_cost<M: Memory + Default>(machine: &State<M>, instruction: &Instruction)
     }
 }
 
-fn new_cost<M: Memory + Default>(machine: &State<M>, instruction: &Instruction) -> Gas {
+fn new_cost<M: Memory + Default, P: Patch>(machine: &State<M>, instruction: &Instruction) -> Gas {
     let address: Address = machine.stack.peek(1).unwrap().into();
-    if instruction == &Instruction::CALL && !machine.account_state.exists(address).unwrap() {
+    if instruction != &Instruction::CALL {
+        return Gas::zero();
+    }
+
+    let is_new = if P::empty_considered_exists() {
+        !machine.account_state.exists(address).unwrap()
+    } else {
+        machine.stack.peek(2).unwrap() != M256::zero() &&
+            machine.account_state.is_empty(address).unwrap()
+    };
+
+    if is_new {
         Gas::from(G_NEWACCOUNT)
     } else {
         Gas::zero()
@@ -72,7 +190,20 @@ fn new_cost<M: Memory + Default>(machine: &State<M>, instruction: &Instruction)
 
 fn suicide_cost<M: Memory + Default, P: Patch>(machine: &State<M>) -> Gas {
     let address: Address = machine.stack.peek(0).unwrap().into();
-    Gas::from(P::gas_suicide()) + if !machine.account_state.exists(address).unwrap() {
+    let is_new = if P::empty_considered_exists() {
+        !machine.account_state.exists(address).unwrap()
+    } else {
+        machine.account_state.balance(machine.context.address).unwrap() != U256::zero() &&
+            machine.account_state.is_empty(address).unwrap()
+    };
+
+    let cold = if is_cold_address::<M, P>(machine, address) {
+        G_COLD_ACCOUNT_ACCESS.into()
+    } else {
+        Gas::zero()
+    };
+
+    cold + Gas::from(P::gas_suicide()) + if is_new {
         Gas::from(P::gas_suicide_new_account())
     } else {
         Gas::zero()
@@ -110,16 +241,24 @@ pub fn memory_cost<M: Memory + Default>(instruction: Instruction, state: &State<
 
     let current = state.memory_cost;
     let next = match instruction {
-        Instruction::SHA3 | Instruction::RETURN | Instruction::LOG(_) => {
+        Instruction::SHA3 | Instruction::RETURN | Instruction::REVERT |
+        Instruction::LOG(_) => {
             let from: U256 = stack.peek(0).unwrap().into();
             let len: U256 = stack.peek(1).unwrap().into();
             memory_expand(current, Gas::from(from), Gas::from(len))
         },
-        Instruction::CODECOPY | Instruction::CALLDATACOPY => {
+        Instruction::CODECOPY | Instruction::CALLDATACOPY | Instruction::RETURNDATACOPY => {
             let from: U256 = stack.peek(0).unwrap().into();
             let len: U256 = stack.peek(2).unwrap().into();
             memory_expand(current, Gas::from(from), Gas::from(len))
         },
+        Instruction::MCOPY => {
+            let from: U256 = stack.peek(0).unwrap().into();
+            let source: U256 = stack.peek(1).unwrap().into();
+            let len: U256 = stack.peek(2).unwrap().into();
+            memory_expand(memory_expand(current, Gas::from(from), Gas::from(len)),
+                          Gas::from(source), Gas::from(len))
+        },
         Instruction::EXTCODECOPY => {
             let from: U256 = stack.peek(1).unwrap().into();
             let len: U256 = stack.peek(3).unwrap().into();
@@ -133,7 +272,7 @@ pub fn memory_cost<M: Memory + Default>(instruction: Instruction, state: &State<
             let from: U256 = stack.peek(0).unwrap().into();
             memory_expand(current, Gas::from(from), Gas::from(1u64))
         },
-        Instruction::CREATE => {
+        Instruction::CREATE | Instruction::CREATE2 => {
             let from: U256 = stack.peek(1).unwrap().into();
             let len: U256 = stack.peek(2).unwrap().into();
             memory_expand(current, Gas::from(from), Gas::from(len))
@@ -146,6 +285,14 @@ pub fn memory_cost<M: Memory + Default>(instruction: Instruction, state: &State<
             memory_expand(memory_expand(current, Gas::from(in_from), Gas::from(in_len)),
                           Gas::from(out_from), Gas::from(out_len))
         },
+        Instruction::STATICCALL => {
+            let in_from: U256 = stack.peek(2).unwrap().into();
+            let in_len: U256 = stack.peek(3).unwrap().into();
+            let out_from: U256 = stack.peek(4).unwrap().into();
+            let out_len: U256 = stack.peek(5).unwrap().into();
+            memory_expand(memory_expand(current, Gas::from(in_from), Gas::from(in_len)),
+                          Gas::from(out_from), Gas::from(out_len))
+        },
         _ => {
             current
         }
@@ -159,8 +306,9 @@ pub fn gas_cost<M: Memory + Default, P: Patch>(instruction: Instruction, state:
         Instruction::CALL => call_cost::<M, P>(state, &Instruction::CALL),
         Instruction::CALLCODE => call_cost::<M, P>(state, &Instruction::CALLCODE),
         Instruction::DELEGATECALL => call_cost::<M, P>(state, &Instruction::DELEGATECALL),
+        Instruction::STATICCALL => call_cost::<M, P>(state, &Instruction::STATICCALL),
         Instruction::SUICIDE => suicide_cost::<M, P>(state),
-        Instruction::SSTORE => sstore_cost(state),
+        Instruction::SSTORE => sstore_cost::<M, P>(state),
 
         Instruction::SHA3 => {
             let len = state.stack.peek(1).unwrap();
@@ -178,10 +326,12 @@ pub fn gas_cost<M: Memory + Default, P: Patch>(instruction: Instruction, state:
             let len = state.stack.peek(3).unwrap();
             let wordd = Gas::from(len) / Gas::from(32u64);
             let wordr = Gas::from(len) % Gas::from(32u64);
-            (Gas::from(P::gas_extcode()) + Gas::from(G_COPY) * if wordr == Gas::zero() { wordd } else { wordd + Gas::from(1u64) }).into()
+            let address: Address = state.stack.peek(0).unwrap().into();
+            (account_access_cost::<M, P>(state, address, P::gas_extcode()) + Gas::from(G_COPY) * if wordr == Gas::zero() { wordd } else { wordd + Gas::from(1u64) }).into()
         },
 
-        Instruction::CALLDATACOPY | Instruction::CODECOPY => {
+        Instruction::CALLDATACOPY | Instruction::CODECOPY | Instruction::RETURNDATACOPY |
+        Instruction::MCOPY => {
             let len = state.stack.peek(2).unwrap();
             let wordd = Gas::from(len) / Gas::from(32u64);
             let wordr = Gas::from(len) % Gas::from(32u64);
@@ -196,35 +346,49 @@ pub fn gas_cost<M: Memory + Default, P: Patch>(instruction: Instruction, state:
             }
         }
 
-        Instruction::CREATE => G_CREATE.into(),
+        Instruction::CREATE => {
+            let len = state.stack.peek(2).unwrap();
+            let wordd = Gas::from(len) / Gas::from(32u64);
+            let wordr = Gas::from(len) % Gas::from(32u64);
+            Gas::from(G_CREATE) + P::gas_initcode_word() * if wordr == Gas::zero() { wordd } else { wordd + Gas::from(1u64) }
+        },
+        Instruction::CREATE2 => {
+            let len = state.stack.peek(2).unwrap();
+            let wordd = Gas::from(len) / Gas::from(32u64);
+            let wordr = Gas::from(len) % Gas::from(32u64);
+            Gas::from(G_CREATE) + (Gas::from(G_SHA3WORD) + P::gas_initcode_word()) * if wordr == Gas::zero() { wordd } else { wordd + Gas::from(1u64) }
+        },
         Instruction::JUMPDEST => G_JUMPDEST.into(),
-        Instruction::SLOAD => P::gas_sload(),
+        Instruction::SLOAD => sload_cost::<M, P>(state),
+        Instruction::TLOAD | Instruction::TSTORE => G_WARM_STORAGE_READ.into(),
 
         // W_zero
-        Instruction::STOP | Instruction::RETURN
+        Instruction::STOP | Instruction::RETURN | Instruction::REVERT
             => G_ZERO.into(),
 
         // W_base
         Instruction::ADDRESS | Instruction::ORIGIN | Instruction::CALLER |
-        Instruction::CALLVALUE | Instruction::CALLDATASIZE |
+        Instruction::CALLVALUE | Instruction::CALLDATASIZE | Instruction::RETURNDATASIZE |
         Instruction::CODESIZE | Instruction::GASPRICE | Instruction::COINBASE |
         Instruction::TIMESTAMP | Instruction::NUMBER | Instruction::DIFFICULTY |
         Instruction::GASLIMIT | Instruction::POP | Instruction::PC |
-        Instruction::MSIZE | Instruction::GAS
+        Instruction::MSIZE | Instruction::GAS | Instruction::CHAINID | Instruction::BASEFEE |
+        Instruction::PUSH0 | Instruction::BLOBBASEFEE
             => G_BASE.into(),
 
         // W_verylow
         Instruction::ADD | Instruction::SUB | Instruction::NOT | Instruction::LT |
         Instruction::GT | Instruction::SLT | Instruction::SGT | Instruction::EQ |
         Instruction::ISZERO | Instruction::AND | Instruction::OR | Instruction::XOR |
-        Instruction::BYTE | Instruction::CALLDATALOAD | Instruction::MLOAD |
+        Instruction::BYTE | Instruction::SHL | Instruction::SHR | Instruction::SAR |
+        Instruction::CALLDATALOAD | Instruction::MLOAD |
         Instruction::MSTORE | Instruction::MSTORE8 | Instruction::PUSH(_) |
-        Instruction::DUP(_) | Instruction::SWAP(_)
+        Instruction::DUP(_) | Instruction::SWAP(_) | Instruction::BLOBHASH
             => G_VERYLOW.into(),
 
         // W_low
         Instruction::MUL | Instruction::DIV | Instruction::SDIV | Instruction::MOD |
-        Instruction::SMOD | Instruction::SIGNEXTEND
+        Instruction::SMOD | Instruction::SIGNEXTEND | Instruction::SELFBALANCE
             => G_LOW.into(),
 
         // W_mid
@@ -235,8 +399,12 @@ pub fn gas_cost<M: Memory + Default, P: Patch>(instruction: Instruction, state:
         Instruction::JUMPI => G_HIGH.into(),
 
         // W_extcode
-        Instruction::EXTCODESIZE => P::gas_extcode(),
-        Instruction::BALANCE => P::gas_balance(),
+        Instruction::EXTCODESIZE => account_access_cost::<M, P>(
+            state, state.stack.peek(0).unwrap().into(), P::gas_extcode()),
+        Instruction::EXTCODEHASH => account_access_cost::<M, P>(
+            state, state.stack.peek(0).unwrap().into(), P::gas_extcodehash()),
+        Instruction::BALANCE => account_access_cost::<M, P>(
+            state, state.stack.peek(0).unwrap().into(), P::gas_balance()),
         Instruction::BLOCKHASH => G_BLOCKHASH.into(),
     }
 }
@@ -257,27 +425,75 @@ pub fn gas_stipend<M: Memory + Default>(instruction: Instruction, state: &State<
     }
 }
 
-/// Calculate the refunded gas.
-pub fn gas_refund<M: Memory + Default>(instruction: Instruction, state: &State<M>) -> Gas {
+/// Calculate the refunded gas. This can be negative when net gas
+/// metering takes back a previously granted refund.
+pub fn gas_refund<M: Memory + Default, P: Patch>(instruction: Instruction, state: &State<M>) -> isize {
     match instruction {
-        Instruction::SSTORE => {
-            let index: U256 = state.stack.peek(0).unwrap().into();
-            let value = state.stack.peek(1).unwrap();
-            let address = state.context.address;
-
-            if value == M256::zero() && state.account_state.storage(address).unwrap().read(index).unwrap() != M256::zero() {
-                Gas::from(R_SCLEAR)
-            } else {
-                Gas::zero()
-            }
-        },
+        Instruction::SSTORE => sstore_refund::<M, P>(state),
         Instruction::SUICIDE => {
             if state.removed.contains(&state.context.address) {
-                Gas::zero()
+                0
             } else {
-                Gas::from(R_SUICIDE)
+                P::gas_refund_suicide().as_u64() as isize
             }
         },
-        _ => Gas::zero()
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigint::{M256, U256, Gas};
+    use patch::{ConstantinoplePatch, BerlinPatch};
+    use transaction::tests::{run, target};
+    use {AccountCommitment, VM};
+
+    #[test]
+    fn sstore_net_gas_metering() {
+        // Test vectors from EIP-1283: code, original value, used gas
+        // and refund.
+        let vectors: [(&str, u64, u64, u64); 17] = [
+            ("0x60006000556000600055", 0, 412, 0),
+            ("0x60006000556001600055", 0, 20212, 0),
+            ("0x60016000556000600055", 0, 20212, 19800),
+            ("0x60016000556002600055", 0, 20212, 0),
+            ("0x60016000556001600055", 0, 20212, 0),
+            ("0x60006000556000600055", 1, 5212, 15000),
+            ("0x60006000556001600055", 1, 5212, 4800),
+            ("0x60006000556002600055", 1, 5212, 0),
+            ("0x60026000556000600055", 1, 5212, 15000),
+            ("0x60026000556003600055", 1, 5212, 0),
+            ("0x60026000556001600055", 1, 5212, 4800),
+            ("0x60026000556002600055", 1, 5212, 0),
+            ("0x60016000556000600055", 1, 5212, 15000),
+            ("0x60016000556002600055", 1, 5212, 0),
+            ("0x60016000556001600055", 1, 412, 0),
+            ("0x600160005560006000556001600055", 0, 40218, 19800),
+            ("0x600060005560016000556000600055", 1, 10218, 19800),
+        ];
+
+        for &(code, original, used_gas, refund) in vectors.iter() {
+            let vm = run::<ConstantinoplePatch>(code, &[], vec![AccountCommitment::Storage {
+                address: target(),
+                index: U256::zero(),
+                value: M256::from(original),
+            }]);
+
+            assert_eq!(Gas::from(100000u64) - vm.available_gas(),
+                       Gas::from(21000 + used_gas), "{}", code);
+            assert_eq!(vm.refunded_gas(), Gas::from(refund), "{}", code);
+        }
+    }
+
+    #[test]
+    fn access_tracking() {
+        // ADDRESS BALANCE PUSH1 0x01 BALANCE PUSH1 0xff BALANCE PUSH1 0xff BALANCE
+        // PUSH1 0x00 SLOAD PUSH1 0x00 SLOAD STOP
+        let vm = run::<BerlinPatch>("0x303160013160ff3160ff3160005460005400", &[], Vec::new());
+
+        // The callee and the precompiled contracts are warm from the
+        // start, everything else is cold on first access.
+        let execution = 2 + 100 + 3 + 100 + 3 + 2600 + 3 + 100 + 3 + 2100 + 3 + 100;
+        assert_eq!(vm.real_used_gas(), Gas::from(21000u64 + execution));
     }
 }
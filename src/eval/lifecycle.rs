@@ -21,9 +21,26 @@ use super::cost::code_deposit_gas;
 /// transaction. After that, it should call `finalize`.
 
 impl<M: Memory + Default, P: Patch> Machine<M, P> {
+    /// Mark the caller, the callee and all precompiled contracts as
+    /// accessed at the start of a transaction (EIP-2929).
+    fn access_initial(&mut self) {
+        if !P::has_access_tracking() {
+            return;
+        }
+
+        self.state.account_state.access_address(self.state.context.caller);
+        self.state.account_state.access_address(self.state.context.address);
+        for precompiled in P::precompileds() {
+            self.state.account_state.access_address(precompiled.0);
+        }
+    }
+
     /// Initialize a MessageCall transaction.
     pub fn initialize_call(&mut self, preclaimed_value: U256) {
+        self.access_initial();
         self.state.account_state.premark_exists(self.state.context.address);
+        let address = self.state.context.address;
+        self.state.touch(address);
 
         if !self.state.context.is_system {
             self.state.account_state.decrease_balance(self.state.context.caller, preclaimed_value);
@@ -35,6 +52,8 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
     /// Initialize the runtime as a call from a CALL or CALLCODE opcode.
     pub fn invoke_call(&mut self) {
         self.state.account_state.premark_exists(self.state.context.address);
+        let address = self.state.context.address;
+        self.state.touch(address);
 
         if !self.state.context.is_system {
             self.state.account_state.decrease_balance(self.state.context.caller, self.state.context.value);
@@ -45,6 +64,7 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
     /// Initialize a ContractCreation transaction.
     pub fn initialize_create(&mut self, preclaimed_value: U256) -> Result<(), RequireError> {
         self.state.account_state.require(self.state.context.address)?;
+        self.access_initial();
 
         self.state.account_state.premark_exists(self.state.context.address);
         if !self.state.context.is_system {
@@ -52,6 +72,9 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
             self.state.account_state.decrease_balance(self.state.context.caller, self.state.context.value);
         }
         self.state.account_state.create(self.state.context.address, self.state.context.value).unwrap();
+        if P::create_increase_nonce() {
+            self.state.account_state.set_nonce(self.state.context.address, U256::from(1u64)).unwrap();
+        }
 
         Ok(())
     }
@@ -65,6 +88,9 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
             self.state.account_state.decrease_balance(self.state.context.caller, self.state.context.value);
         }
         self.state.account_state.create(self.state.context.address, self.state.context.value).unwrap();
+        if P::create_increase_nonce() {
+            self.state.account_state.set_nonce(self.state.context.address, U256::from(1u64)).unwrap();
+        }
 
         Ok(())
     }
@@ -73,9 +99,23 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
     pub fn code_deposit(&mut self) {
         match self.status() {
             MachineStatus::ExitedOk | MachineStatus::ExitedErr(_) => (),
+            MachineStatus::ExitedRevert => return,
             _ => panic!(),
         }
 
+        match P::max_code_size() {
+            Some(limit) if self.state.out.len() > limit => {
+                self.status = MachineStatus::ExitedErr(OnChainError::CodeSizeLimit);
+                return;
+            },
+            _ => (),
+        }
+
+        if P::reject_ef_code() && self.state.out.first() == Some(&0xef) {
+            self.status = MachineStatus::ExitedErr(OnChainError::InvalidCodePrefix);
+            return;
+        }
+
         let deposit_cost = code_deposit_gas(self.state.out.len());
         if deposit_cost > self.state.available_gas() {
             if !P::force_code_deposit() {
@@ -94,6 +134,9 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
     /// by an opcode.
     pub fn finalize(&mut self, real_used_gas: Gas, preclaimed_value: U256, fresh_account_state: &AccountState) -> Result<(), RequireError> {
         self.state.account_state.require(self.state.context.address)?;
+        if !P::empty_considered_exists() {
+            self.state.account_state.require(self.state.block.beneficiary)?;
+        }
 
         match self.status() {
             MachineStatus::ExitedOk => {
@@ -101,15 +144,23 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 for address in &self.state.removed {
                     self.state.account_state.require(*address)?;
                 }
+                // Requires touched accounts to exist, to check
+                // whether they are empty.
+                if !P::empty_considered_exists() {
+                    for address in &self.state.touched {
+                        self.state.account_state.require(*address)?;
+                    }
+                }
             },
-            MachineStatus::ExitedErr(_) => {
-                // If exited with error, reset all changes.
+            MachineStatus::ExitedErr(_) | MachineStatus::ExitedRevert => {
+                // If exited with error or reverted, reset all changes.
                 self.state.account_state = fresh_account_state.clone();
                 if !self.state.context.is_system {
                     self.state.account_state.decrease_balance(self.state.context.caller, preclaimed_value);
                 }
                 self.state.logs = Vec::new();
                 self.state.removed = Vec::new();
+                self.state.touched = Vec::new();
             },
             _ => panic!(),
         }
@@ -120,16 +171,43 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
             self.state.account_state.decrease_balance(self.state.context.caller, gas_dec.into());
         }
 
-        // Apply miner rewards
-        self.state.account_state.increase_balance(self.state.block.beneficiary, gas_dec.into());
+        // Apply miner rewards. With a base fee, only the priority fee
+        // goes to the beneficiary and the rest is burnt.
+        let reward = if P::has_base_fee() {
+            let gas_price = self.state.context.gas_price;
+            let base_fee = self.state.block.base_fee;
+            if gas_price > base_fee {
+                real_used_gas * (gas_price - base_fee)
+            } else {
+                Gas::zero()
+            }
+        } else {
+            gas_dec
+        };
+        self.state.account_state.increase_balance(self.state.block.beneficiary, reward.into());
+        let beneficiary = self.state.block.beneficiary;
+        self.state.touch(beneficiary);
 
         for address in &self.state.removed {
             self.state.account_state.remove(*address).unwrap();
         }
 
+        if !P::empty_considered_exists() {
+            for address in &self.state.touched {
+                if self.state.account_state.exists(*address).unwrap() &&
+                    self.state.account_state.is_empty(*address).unwrap() &&
+                    !self.state.removed.contains(address)
+                {
+                    self.state.account_state.remove(*address).unwrap();
+                    self.state.removed.push(*address);
+                }
+            }
+        }
+
         match self.status() {
             MachineStatus::ExitedOk => Ok(()),
             MachineStatus::ExitedErr(_) => Ok(()),
+            MachineStatus::ExitedRevert => Ok(()),
             _ => panic!(),
         }
     }
@@ -166,17 +244,26 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 let sub_total_used_gas = sub.state.total_used_gas();
 
                 self.state.account_state = sub.state.account_state;
+                self.state.transient_storage = sub.state.transient_storage;
                 self.state.blockhash_state = sub.state.blockhash_state;
                 self.state.logs = sub.state.logs;
                 self.state.removed = sub.state.removed;
+                self.state.touched = sub.state.touched;
                 self.state.used_gas = self.state.used_gas + sub_total_used_gas;
-                self.state.refunded_gas = self.state.refunded_gas + sub.state.refunded_gas;
-
+                self.state.refunded_gas = sub.state.refunded_gas;
+                self.state.ret = Vec::new();
             },
             MachineStatus::ExitedErr(_) => {
                 self.state.used_gas = self.state.used_gas + sub.state.context.gas_limit;
                 self.state.stack.pop().unwrap();
                 self.state.stack.push(M256::zero()).unwrap();
+                self.state.ret = Vec::new();
+            },
+            MachineStatus::ExitedRevert => {
+                self.state.used_gas = self.state.used_gas + sub.state.total_used_gas();
+                self.state.stack.pop().unwrap();
+                self.state.stack.push(M256::zero()).unwrap();
+                self.state.ret = sub.state.out;
             },
             _ => panic!(),
         }
@@ -192,20 +279,63 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 let sub_total_used_gas = sub.state.total_used_gas();
 
                 self.state.account_state = sub.state.account_state;
+                self.state.transient_storage = sub.state.transient_storage;
                 self.state.blockhash_state = sub.state.blockhash_state;
                 self.state.logs = sub.state.logs;
                 self.state.removed = sub.state.removed;
+                self.state.touched = sub.state.touched;
                 self.state.used_gas = self.state.used_gas + sub_total_used_gas;
-                self.state.refunded_gas = self.state.refunded_gas + sub.state.refunded_gas;
+                self.state.refunded_gas = sub.state.refunded_gas;
                 copy_into_memory_apply(&mut self.state.memory, sub.state.out.as_slice(),
                                        out_start, out_len);
+                self.state.ret = sub.state.out;
             },
             MachineStatus::ExitedErr(_) => {
                 self.state.used_gas = self.state.used_gas + sub.state.context.gas_limit;
                 self.state.stack.pop().unwrap();
                 self.state.stack.push(M256::zero()).unwrap();
+                self.state.ret = Vec::new();
+            },
+            MachineStatus::ExitedRevert => {
+                self.state.used_gas = self.state.used_gas + sub.state.total_used_gas();
+                self.state.stack.pop().unwrap();
+                self.state.stack.push(M256::zero()).unwrap();
+                copy_into_memory_apply(&mut self.state.memory, sub.state.out.as_slice(),
+                                       out_start, out_len);
+                self.state.ret = sub.state.out;
             },
             _ => panic!(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bigint::Gas;
+    use block::TransactionAction;
+    use errors::OnChainError;
+    use patch::{BerlinPatch, LondonPatch};
+    use transaction::tests::{execute, transaction, block};
+    use {Patch, SeqTransactionVM, ValidTransaction, VM, VMStatus};
+
+    fn deploy_ef_code<P: Patch>() -> SeqTransactionVM<P> {
+        execute::<P>(ValidTransaction {
+            gas_limit: Gas::from(200000u64),
+            action: TransactionAction::Create,
+            // PUSH1 0xef PUSH1 0x00 MSTORE8 PUSH1 0x01 PUSH1 0x00 RETURN
+            input: vec![0x60, 0xef, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3],
+            ..transaction()
+        }, block(), Vec::new())
+    }
+
+    #[test]
+    fn ef_code_prefix() {
+        match deploy_ef_code::<BerlinPatch>().status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        match deploy_ef_code::<LondonPatch>().status() {
+            VMStatus::ExitedErr(OnChainError::InvalidCodePrefix) => (),
+            status => panic!("{:?}", status),
+        }
+    }}
@@ -1,9 +1,10 @@
 //! VM Runtime
+use std::collections::HashMap;
 use bigint::{H256, M256, U256, Gas, Address};
 use super::commit::{AccountState, BlockhashState};
 use super::errors::{RequireError, RuntimeError, CommitError, EvalOnChainError,
                     OnChainError, NotSupportedError};
-use super::{Stack, Context, HeaderParams, Patch, PC, Memory, AccountCommitment, Log};
+use super::{Stack, Context, HeaderParams, Patch, PC, Instruction, Memory, AccountCommitment, Log};
 
 use self::check::{check_opcode, check_support, extra_check_opcode};
 use self::run::run_opcode;
@@ -29,23 +30,31 @@ pub struct State<M> {
 
     /// The current out value.
     pub out: Vec<u8>,
+    /// Return data of the last finished sub call or create.
+    pub ret: Vec<u8>,
 
     /// The current memory cost. Note that this is different from
     /// memory gas.
     pub memory_cost: Gas,
     /// Used gas excluding memory gas.
     pub used_gas: Gas,
-    /// Refunded gas.
+    /// Refunded gas of the whole transaction up to this runtime.
     pub refunded_gas: Gas,
 
     /// The current account commitment states.
     pub account_state: AccountState,
+    /// Transient storage (EIP-1153), keyed by address and index. It
+    /// is never committed and is discarded after the transaction.
+    pub transient_storage: HashMap<(Address, U256), M256>,
     /// The current blockhash commitment states.
     pub blockhash_state: BlockhashState,
     /// Logs appended.
     pub logs: Vec<Log>,
     /// Removed accounts using the SUICIDE opcode.
     pub removed: Vec<Address>,
+    /// Accounts touched during the execution. Empty ones are removed
+    /// when finalizing, unless the patch considers them existing.
+    pub touched: Vec<Address>,
 
     /// Depth of this runtime.
     pub depth: usize,
@@ -66,6 +75,36 @@ impl<M> State<M> {
     pub fn total_used_gas(&self) -> Gas {
         self.memory_gas() + self.used_gas
     }
+
+    /// Mark an account as touched.
+    fn touch(&mut self, address: Address) {
+        if !self.touched.contains(&address) {
+            self.touched.push(address);
+        }
+    }
+
+    /// Mark the account or storage key used by the instruction as
+    /// accessed (EIP-2929).
+    fn access(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::SLOAD | Instruction::SSTORE => {
+                let address = self.context.address;
+                let index: U256 = self.stack.peek(0).unwrap().into();
+                self.account_state.access_storage(address, index);
+            },
+            Instruction::BALANCE | Instruction::EXTCODESIZE | Instruction::EXTCODECOPY |
+            Instruction::EXTCODEHASH | Instruction::SUICIDE => {
+                let address: Address = self.stack.peek(0).unwrap().into();
+                self.account_state.access_address(address);
+            },
+            Instruction::CALL | Instruction::CALLCODE | Instruction::DELEGATECALL |
+            Instruction::STATICCALL => {
+                let address: Address = self.stack.peek(1).unwrap().into();
+                self.account_state.access_address(address);
+            },
+            _ => (),
+        }
+    }
 }
 
 /// A VM state with PC.
@@ -86,6 +125,10 @@ pub enum MachineStatus {
     /// This runtime has exited with errors. Calling `step` on this
     /// runtime again would panic.
     ExitedErr(OnChainError),
+    /// This runtime has exited using the REVERT opcode. State changes
+    /// are discarded but unused gas and the output are kept. Calling
+    /// `step` on this runtime again would panic.
+    ExitedRevert,
     ExitedNotSupported(NotSupportedError),
     /// This runtime requires execution of a sub runtime, which is a
     /// ContractCreation instruction.
@@ -105,6 +148,7 @@ pub enum ControlCheck {
 /// Used for `step` for additional operations related to the runtime.
 pub enum Control {
     Stop,
+    Revert,
     Jump(M256),
     InvokeCreate(Context),
     InvokeCall(Context, (U256, U256)),
@@ -132,15 +176,18 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 block,
 
                 out: Vec::new(),
+                ret: Vec::new(),
 
                 memory_cost: Gas::zero(),
                 used_gas: Gas::zero(),
                 refunded_gas: Gas::zero(),
 
                 account_state,
+                transient_storage: HashMap::new(),
                 blockhash_state,
                 logs: Vec::new(),
                 removed: Vec::new(),
+                touched: Vec::new(),
 
                 depth,
             },
@@ -150,8 +197,11 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
     /// Derive this runtime to create a sub runtime. This will not
     /// modify the current runtime, and it will have a chance to
     /// review whether it wants to accept the result of this sub
-    /// runtime afterwards.
-    pub fn derive(&self, context: Context) -> Self {
+    /// runtime afterwards. A sub runtime of a static runtime is
+    /// always static.
+    pub fn derive(&self, mut context: Context) -> Self {
+        context.is_static = context.is_static || self.state.context.is_static;
+
         Machine {
             pc: PC::new(context.code.as_slice()),
             status: MachineStatus::Running,
@@ -163,15 +213,18 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 block: self.state.block.clone(),
 
                 out: Vec::new(),
+                ret: Vec::new(),
 
                 memory_cost: Gas::zero(),
                 used_gas: Gas::zero(),
-                refunded_gas: Gas::zero(),
+                refunded_gas: self.state.refunded_gas,
 
                 account_state: self.state.account_state.clone(),
+                transient_storage: self.state.transient_storage.clone(),
                 blockhash_state: self.state.blockhash_state.clone(),
                 logs: self.state.logs.clone(),
                 removed: self.state.removed.clone(),
+                touched: self.state.touched.clone(),
 
                 depth: self.state.depth + 1,
             },
@@ -273,7 +326,7 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
         let memory_gas = memory_gas(memory_cost);
         let gas_cost = gas_cost::<M, P>(instruction, &self.state);
         let gas_stipend = gas_stipend(instruction, &self.state);
-        let gas_refund = gas_refund(instruction, &self.state);
+        let gas_refund = gas_refund::<M, P>(instruction, &self.state);
 
         let all_gas_cost = memory_gas + self.state.used_gas + gas_cost;
         if self.state.context.gas_limit < all_gas_cost {
@@ -299,13 +352,21 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
             },
         }
 
+        if P::has_access_tracking() {
+            self.state.access(instruction);
+        }
+
         let instruction = self.pc.read().unwrap();
         let result = run_opcode::<M, P>((instruction, position),
                                         &mut self.state, gas_stipend, after_gas);
 
         self.state.used_gas = self.state.used_gas + gas_cost - gas_stipend;
         self.state.memory_cost = memory_cost;
-        self.state.refunded_gas = self.state.refunded_gas + gas_refund;
+        if gas_refund >= 0 {
+            self.state.refunded_gas = self.state.refunded_gas + Gas::from(gas_refund as u64);
+        } else {
+            self.state.refunded_gas = self.state.refunded_gas - Gas::from((-gas_refund) as u64);
+        }
 
         match result {
             None => Ok(()),
@@ -325,6 +386,10 @@ impl<M: Memory + Default, P: Patch> Machine<M, P> {
                 self.status = MachineStatus::ExitedOk;
                 Ok(())
             },
+            Some(Control::Revert) => {
+                self.status = MachineStatus::ExitedRevert;
+                Ok(())
+            },
         }
     }
 
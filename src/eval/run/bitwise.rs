@@ -1,6 +1,6 @@
 //! Bitwise instructions
 
-use bigint::M256;
+use bigint::{M256, MI256, Sign};
 
 use ::Memory;
 use super::State;
@@ -37,3 +37,59 @@ pub fn byte<M: Memory + Default>(state: &mut State<M>) {
 
     push!(state, ret);
 }
+
+pub fn shl<M: Memory + Default>(state: &mut State<M>) {
+    pop!(state, shift, value);
+
+    if shift >= 256.into() {
+        push!(state, M256::zero());
+    } else {
+        push!(state, value << shift.as_usize());
+    }
+}
+
+pub fn shr<M: Memory + Default>(state: &mut State<M>) {
+    pop!(state, shift, value);
+
+    if shift >= 256.into() {
+        push!(state, M256::zero());
+    } else {
+        push!(state, value >> shift.as_usize());
+    }
+}
+
+pub fn sar<M: Memory + Default>(state: &mut State<M>) {
+    pop!(state, shift, value);
+
+    let negative = MI256::from(value).0 == Sign::Minus;
+    if shift >= 256.into() {
+        if negative {
+            push!(state, !M256::zero());
+        } else {
+            push!(state, M256::zero());
+        }
+    } else if negative {
+        push!(state, !(!value >> shift.as_usize()));
+    } else {
+        push!(state, value >> shift.as_usize());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patch::ConstantinoplePatch;
+    use transaction::tests::run;
+    use VM;
+
+    #[test]
+    fn sar_negative() {
+        // PUSH32 -16 PUSH1 0x02 SAR PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+        let vm = run::<ConstantinoplePatch>(
+            "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff0\
+             60021d60005260206000f3", &[], Vec::new());
+
+        let mut expected = [0xffu8; 32];
+        expected[31] = 0xfc;
+        assert_eq!(vm.out(), expected.as_ref());
+    }
+}
@@ -15,6 +15,23 @@ pub fn sstore<M: Memory + Default>(state: &mut State<M>) {
     state.account_state.storage_mut(state.context.address).unwrap().write(index, value).unwrap();
 }
 
+pub fn tload<M: Memory + Default>(state: &mut State<M>) {
+    pop!(state, index: U256);
+    let value = state.transient_storage.get(&(state.context.address, index))
+        .cloned().unwrap_or(M256::zero());
+    push!(state, value);
+}
+
+pub fn tstore<M: Memory + Default>(state: &mut State<M>) {
+    pop!(state, index: U256, value: M256);
+    let key = (state.context.address, index);
+    if value == M256::zero() {
+        state.transient_storage.remove(&key);
+    } else {
+        state.transient_storage.insert(key, value);
+    }
+}
+
 pub fn mload<M: Memory + Default>(state: &mut State<M>) {
     pop!(state, index: U256);
     let value = state.memory.read(index);
@@ -30,3 +47,23 @@ pub fn mstore8<M: Memory + Default>(state: &mut State<M>) {
     pop!(state, index: U256, value: M256);
     state.memory.write_raw(index, (value.0.low_u32() & 0xFF) as u8).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use bigint::U256;
+    use patch::CancunPatch;
+    use transaction::tests::run;
+    use VM;
+
+    #[test]
+    fn transient_storage() {
+        // Without call data: TSTORE(0, 1), call itself with one byte
+        // of call data, then return TLOAD(0). With call data:
+        // TSTORE(0, 2), then STOP or REVERT.
+        let main = "0x36601a5760015f5d5f5f60015f5f305af1505f5c5f5260205ff3";
+        let vm = run::<CancunPatch>(&format!("{}5b60025f5d000000", main), &[], Vec::new());
+        assert_eq!(U256::from(vm.out()), U256::from(2u64));
+        let vm = run::<CancunPatch>(&format!("{}5b60025f5d5f5ffd", main), &[], Vec::new());
+        assert_eq!(U256::from(vm.out()), U256::from(1u64));
+    }
+}
@@ -83,6 +83,9 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
         Instruction::XOR => { op2!(state, bitxor); None },
         Instruction::NOT => { bitwise::not(state); None },
         Instruction::BYTE => { bitwise::byte(state); None },
+        Instruction::SHL => { bitwise::shl(state); None },
+        Instruction::SHR => { bitwise::shr(state); None },
+        Instruction::SAR => { bitwise::sar(state); None },
 
         Instruction::SHA3 => { system::sha3(state); None },
 
@@ -117,6 +120,25 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
                                                        state.account_state.code(address).unwrap(),
                                                        memory_index, code_index, len);
                                       None },
+        Instruction::EXTCODEHASH => { pop!(state, address: Address);
+                                      let exists = !state.account_state.code(address).unwrap().is_empty() ||
+                                          if P::empty_considered_exists() {
+                                              state.account_state.exists(address).unwrap()
+                                          } else {
+                                              !state.account_state.is_empty(address).unwrap()
+                                          };
+                                      if exists {
+                                          push!(state, M256::from(state.account_state.code_hash(address).unwrap()));
+                                      } else {
+                                          push!(state, M256::zero());
+                                      }
+                                      None },
+        Instruction::RETURNDATASIZE => { push!(state, state.ret.len().into()); None },
+        Instruction::RETURNDATACOPY => { pop!(state, memory_index: U256, data_index: U256, len: U256);
+                                         copy_into_memory(&mut state.memory,
+                                                          state.ret.as_slice(),
+                                                          memory_index, data_index, len);
+                                         None },
 
         Instruction::BLOCKHASH => { pop!(state, number: U256);
                                     let current_number = state.block.number;
@@ -131,6 +153,24 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
         Instruction::NUMBER => { push!(state, M256::from(state.block.number)); None },
         Instruction::DIFFICULTY => { push!(state, M256::from(state.block.difficulty)); None },
         Instruction::GASLIMIT => { push!(state, state.block.gas_limit.into()); None },
+        // CHAINID is only decoded for patches with a chain ID.
+        Instruction::CHAINID => { push!(state, P::chain_id().unwrap().into()); None },
+        Instruction::BASEFEE => { push!(state, state.block.base_fee.into()); None },
+        Instruction::BLOBHASH => { pop!(state, index: U256);
+                                   let hash = if index < U256::from(state.context.blob_hashes.len()) {
+                                       state.context.blob_hashes[index.as_usize()].into()
+                                   } else {
+                                       M256::zero()
+                                   };
+                                   push!(state, hash);
+                                   None },
+        Instruction::BLOBBASEFEE => { push!(state, state.block.blob_base_fee().into()); None },
+        // The executing account is always committed in full before its
+        // code runs, so SELFBALANCE does not require it.
+        Instruction::SELFBALANCE => { let balance = state.account_state.balance(state.context.address)
+                                          .unwrap_or(U256::zero());
+                                      push!(state, balance.into());
+                                      None },
 
         Instruction::POP => { state.stack.pop().unwrap(); None },
         Instruction::MLOAD => { flow::mload(state); None },
@@ -138,6 +178,13 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
         Instruction::MSTORE8 => { flow::mstore8(state); None },
         Instruction::SLOAD => { flow::sload(state); None },
         Instruction::SSTORE => { flow::sstore(state); None },
+        Instruction::TLOAD => { flow::tload(state); None },
+        Instruction::TSTORE => { flow::tstore(state); None },
+        Instruction::MCOPY => { pop!(state, memory_index: U256, source_index: U256, len: U256);
+                                let values = copy_from_memory(&state.memory, source_index, len);
+                                copy_into_memory(&mut state.memory, values.as_slice(),
+                                                 memory_index, U256::zero(), len);
+                                None },
         Instruction::JUMP => { pop!(state, dest); Some(Control::Jump(dest)) }
         Instruction::JUMPI => { pop!(state, dest, value);
                                 if value != M256::zero() {
@@ -149,6 +196,7 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
         Instruction::MSIZE => { push!(state, (state.memory_cost * Gas::from(32u64)).into()); None },
         Instruction::GAS => { push!(state, after_gas.into()); None },
         Instruction::JUMPDEST => None,
+        Instruction::PUSH0 => { push!(state, M256::zero()); None },
 
         Instruction::PUSH(v) => { push!(state, v); None }
 
@@ -162,13 +210,125 @@ pub fn run_opcode<M: Memory + Default, P: Patch>(pc: (Instruction, usize), state
                                   None },
         Instruction::LOG(v) => { system::log(state, v); None },
 
-        Instruction::CREATE => { system::create::<M, P>(state, after_gas) },
+        Instruction::CREATE => { system::create::<M, P>(state, after_gas, false) },
+        Instruction::CREATE2 => { system::create::<M, P>(state, after_gas, true) },
         Instruction::CALL => { system::call::<M, P>(state, stipend_gas, after_gas, false) },
         Instruction::CALLCODE => { system::call::<M, P>(state, stipend_gas, after_gas, true) },
         Instruction::DELEGATECALL => { system::delegate_call::<M, P>(state, after_gas) },
+        Instruction::STATICCALL => { system::static_call::<M, P>(state, after_gas) },
         Instruction::RETURN => { pop!(state, start: U256, len: U256);
                                  state.out = copy_from_memory(&mut state.memory, start, len);
                                  Some(Control::Stop) },
+        Instruction::REVERT => { pop!(state, start: U256, len: U256);
+                                 state.out = copy_from_memory(&mut state.memory, start, len);
+                                 Some(Control::Revert) },
         Instruction::SUICIDE => { system::suicide(state); Some(Control::Stop) },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bigint::{U256, Gas};
+    use block::GlobalSignaturePatch;
+    use hexutil::read_hex;
+    use errors::OnChainError;
+    use patch::{ConstantinoplePatch, IstanbulPatch, LondonPatch, ShanghaiPatch, CancunPatch,
+                FoundationSignaturePatch};
+    use sha3::{Digest, Keccak256};
+    use transaction::tests::{run, execute, transaction, block, target};
+    use {AccountCommitment, Patch, PC, SeqTransactionVM, VM, VMStatus};
+
+    #[test]
+    fn extcodehash() {
+        // ADDRESS EXTCODEHASH PUSH1 0x00 MSTORE PUSH1 0x02 EXTCODEHASH PUSH1 0x20 MSTORE
+        // PUSH1 0x40 PUSH1 0x00 RETURN
+        let code = "0x303f60005260023f60205260406000f3";
+        let vm = run::<ConstantinoplePatch>(code, &[], Vec::new());
+
+        assert_eq!(&vm.out()[0..32], Keccak256::digest(&read_hex(code).unwrap()).as_slice());
+        assert_eq!(&vm.out()[32..64], &[0u8; 32]);
+    }
+
+    fn chain_id_and_self_balance<P: Patch>() -> Vec<u8> {
+        let vm = execute::<P>(transaction(), block(), vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::from(1234u64),
+            // CHAINID PUSH1 0 MSTORE SELFBALANCE PUSH1 32 MSTORE PUSH1 64 PUSH1 0 RETURN
+            code: read_hex("0x466000524760205260406000f3").unwrap(),
+        }]);
+
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(Gas::from(100000u64) - vm.available_gas(), Gas::from(21000 + 31u64));
+        vm.out().to_vec()
+    }
+
+    #[test]
+    fn chain_id_and_self_balance_opcodes() {
+        let mut expected = [0u8; 64];
+        U256::from(61u64).to_big_endian(&mut expected[0..32]);
+        U256::from(1234u64).to_big_endian(&mut expected[32..64]);
+        assert_eq!(chain_id_and_self_balance::<IstanbulPatch>(), &expected[..]);
+
+        U256::from(1u64).to_big_endian(&mut expected[0..32]);
+        assert_eq!(chain_id_and_self_balance::<IstanbulPatch<FoundationSignaturePatch>>(), &expected[..]);
+
+        // CHAINID is an invalid opcode if the patch has no chain ID.
+        let vm = execute::<IstanbulPatch<GlobalSignaturePatch>>(transaction(), block(), vec![
+            AccountCommitment::Full {
+                nonce: U256::zero(),
+                address: target(),
+                balance: U256::zero(),
+                code: read_hex("0x46").unwrap(),
+            }]);
+        match vm.status() {
+            VMStatus::ExitedErr(OnChainError::InvalidOpcode) => (),
+            status => panic!("{:?}", status),
+        }
+    }
+
+    fn push0<P: Patch>() -> SeqTransactionVM<P> {
+        execute::<P>(transaction(), block(), vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // PUSH1 0x2a PUSH0 MSTORE PUSH1 0x20 PUSH0 RETURN
+            code: read_hex("0x602a5f5260205ff3").unwrap(),
+        }])
+    }
+
+    #[test]
+    fn push0_opcode() {
+        let vm = push0::<ShanghaiPatch>();
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(U256::from(vm.out()), U256::from(42u64));
+        assert_eq!(Gas::from(100000u64) - vm.available_gas(), Gas::from(21000 + 16u64));
+
+        match push0::<LondonPatch>().status() {
+            VMStatus::ExitedErr(OnChainError::InvalidOpcode) => (),
+            status => panic!("{:?}", status),
+        }
+
+        // PUSH0 has no immediate, so the following JUMPDEST is valid.
+        assert!(PC::<ShanghaiPatch>::new(&[0x5f, 0x5b]).is_valid(1));
+    }
+
+    #[test]
+    fn mcopy_overlapping() {
+        // PUSH32 0x000102..1f PUSH0 MSTORE PUSH1 8 PUSH0 PUSH1 1 MCOPY
+        // PUSH1 32 PUSH0 RETURN
+        let vm = run::<CancunPatch>(
+            "0x7f000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+             5f5260085f60015e60205ff3", &[], Vec::new());
+
+        assert_eq!(vm.out(), read_hex("0x000001020304050607090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+                   .unwrap().as_slice());
+        assert_eq!(Gas::from(100000u64) - vm.available_gas(), Gas::from(21000 + 30u64));
+    }
+}
@@ -11,6 +11,7 @@ use sha3::{Digest, Keccak256};
 
 pub fn suicide<M: Memory + Default>(state: &mut State<M>) {
     pop!(state, address: Address);
+    state.touch(address);
     let balance = state.account_state.balance(state.context.address).unwrap();
     if !state.removed.contains(&state.context.address) {
         state.removed.push(state.context.address);
@@ -61,11 +62,18 @@ macro_rules! try_balance {
     }
 }
 
-pub fn create<M: Memory + Default, P: Patch>(state: &mut State<M>, after_gas: Gas) -> Option<Control> {
+pub fn create<M: Memory + Default, P: Patch>(state: &mut State<M>, after_gas: Gas, salted: bool) -> Option<Control> {
     let l64_after_gas = if P::call_create_l64_after_gas() { l64(after_gas) } else { after_gas };
 
     pop!(state, value: U256);
     pop!(state, init_start: U256, init_len: U256);
+    let salt = if salted {
+        pop!(state, salt: H256);
+        Some(salt)
+    } else {
+        None
+    };
+    state.ret = Vec::new();
 
     try_callstack_limit!(state, P);
     try_balance!(state, value, Gas::zero());
@@ -79,21 +87,41 @@ pub fn create<M: Memory + Default, P: Patch>(state: &mut State<M>, after_gas: Ga
         input: init,
         action: TransactionAction::Create,
         nonce: state.account_state.nonce(state.context.address).unwrap(),
+        access_list: Vec::new(),
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: state.context.blob_hashes.clone(),
     };
-    let context = transaction.into_context(
+    let mut context = transaction.into_context(
         Gas::zero(), Some(state.context.origin), &mut state.account_state, true
     ).unwrap();
+    if let Some(salt) = salt {
+        context.address = create2_address(state.context.address, salt, &context.code);
+    }
+    if P::has_access_tracking() {
+        state.account_state.access_address(context.address);
+    }
 
     push!(state, context.address.into());
     Some(Control::InvokeCreate(context))
 }
 
+fn create2_address(caller: Address, salt: H256, init: &[u8]) -> Address {
+    let mut hasher = Keccak256::default();
+    hasher.input(&[0xff]);
+    hasher.input(&caller);
+    hasher.input(&salt);
+    hasher.input(Keccak256::digest(init).as_slice());
+    Address::from(&hasher.result()[12..])
+}
+
 pub fn call<M: Memory + Default, P: Patch>(state: &mut State<M>, stipend_gas: Gas, after_gas: Gas, as_self: bool) -> Option<Control> {
     let l64_after_gas = if P::call_create_l64_after_gas() { l64(after_gas) } else { after_gas };
 
     pop!(state, gas: Gas, to: Address, value: U256);
     pop!(state, in_start: U256, in_len: U256, out_start: U256, out_len: U256);
     let gas_limit = min(gas, l64_after_gas) + stipend_gas;
+    state.ret = Vec::new();
 
     try_callstack_limit!(state, P);
     try_balance!(state, value, gas_limit);
@@ -107,6 +135,10 @@ pub fn call<M: Memory + Default, P: Patch>(state: &mut State<M>, stipend_gas: Ga
         input: input,
         action: TransactionAction::Call(to),
         nonce: state.account_state.nonce(state.context.address).unwrap(),
+        access_list: Vec::new(),
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: state.context.blob_hashes.clone(),
     };
 
     let mut context = transaction.into_context(
@@ -126,6 +158,7 @@ pub fn delegate_call<M: Memory + Default, P: Patch>(state: &mut State<M>, after_
     pop!(state, gas: Gas, to: Address);
     pop!(state, in_start: U256, in_len: U256, out_start: U256, out_len: U256);
     let gas_limit = min(gas, l64_after_gas);
+    state.ret = Vec::new();
 
     try_callstack_limit!(state, P);
 
@@ -138,6 +171,10 @@ pub fn delegate_call<M: Memory + Default, P: Patch>(state: &mut State<M>, after_
         input: input,
         action: TransactionAction::Call(to),
         nonce: state.account_state.nonce(state.context.address).unwrap(),
+        access_list: Vec::new(),
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: state.context.blob_hashes.clone(),
     };
 
     let mut context = transaction.into_context(
@@ -149,3 +186,55 @@ pub fn delegate_call<M: Memory + Default, P: Patch>(state: &mut State<M>, after_
     push!(state, M256::from(1u64));
     Some(Control::InvokeCall(context, (out_start, out_len)))
 }
+
+pub fn static_call<M: Memory + Default, P: Patch>(state: &mut State<M>, after_gas: Gas) -> Option<Control> {
+    let l64_after_gas = if P::call_create_l64_after_gas() { l64(after_gas) } else { after_gas };
+
+    pop!(state, gas: Gas, to: Address);
+    pop!(state, in_start: U256, in_len: U256, out_start: U256, out_len: U256);
+    let gas_limit = min(gas, l64_after_gas);
+    state.ret = Vec::new();
+
+    try_callstack_limit!(state, P);
+
+    let input = copy_from_memory(&state.memory, in_start, in_len);
+    let transaction = ValidTransaction {
+        caller: Some(state.context.address),
+        gas_price: state.context.gas_price,
+        gas_limit: gas_limit,
+        value: U256::zero(),
+        input: input,
+        action: TransactionAction::Call(to),
+        nonce: state.account_state.nonce(state.context.address).unwrap(),
+        access_list: Vec::new(),
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: state.context.blob_hashes.clone(),
+    };
+
+    let mut context = transaction.into_context(
+        Gas::zero(), Some(state.context.origin), &mut state.account_state, true
+    ).unwrap();
+    context.is_static = true;
+
+    push!(state, M256::from(1u64));
+    Some(Control::InvokeCall(context, (out_start, out_len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use bigint::{H256, Address};
+    use std::str::FromStr;
+    use super::create2_address;
+
+    #[test]
+    fn create2() {
+        // Examples from EIP-1014.
+        assert_eq!(create2_address(Address::default(), H256::default(), &[0x00]),
+                   Address::from_str("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38").unwrap());
+        assert_eq!(create2_address(Address::from_str("0x00000000000000000000000000000000deadbeef").unwrap(),
+                                   H256::from_str("0x00000000000000000000000000000000000000000000000000000000cafebabe").unwrap(),
+                                   &[0xde, 0xad, 0xbe, 0xef]),
+                   Address::from_str("0x60f3f640a8508fC6a86d45DF051962668E1e8AC7").unwrap());
+    }
+}
@@ -26,6 +26,9 @@ extern crate sha2;
 extern crate sha3;
 extern crate secp256k1;
 extern crate digest;
+extern crate num_bigint;
+extern crate substrate_bn as bn;
+extern crate c_kzg;
 #[macro_use]
 extern crate lazy_static;
 
@@ -38,6 +41,7 @@ mod eval;
 mod commit;
 mod patch;
 mod transaction;
+mod typed_transaction;
 pub mod errors;
 
 pub use self::memory::{Memory, SeqMemory};
@@ -47,7 +51,11 @@ pub use self::params::*;
 pub use self::patch::*;
 pub use self::eval::{State, Machine, MachineStatus};
 pub use self::commit::{AccountCommitment, AccountChange, AccountState, BlockhashState, Storage};
-pub use self::transaction::{ValidTransaction, TransactionVM};
+pub use self::transaction::{ValidTransaction, IntrinsicGas, TransactionVM};
+pub use self::typed_transaction::{TypedTransaction, AccessListTransaction, DynamicFeeTransaction,
+                                  BlobTransaction, AccessListItem, TypedTransactionSignature,
+                                  ACCESS_LIST_TRANSACTION_TYPE, DYNAMIC_FEE_TRANSACTION_TYPE,
+                                  BLOB_TRANSACTION_TYPE, VERSIONED_HASH_VERSION_KZG};
 pub use self::errors::{OnChainError, NotSupportedError, RequireError, CommitError, PreExecutionError};
 pub use self::util::opcode::Opcode;
 
@@ -64,6 +72,9 @@ pub enum VMStatus {
     /// VM is stopped due to an error. The state of the VM is before
     /// the last failing instruction.
     ExitedErr(OnChainError),
+    /// VM is stopped by the REVERT opcode. State changes are
+    /// discarded, and the revert data is available as the output.
+    ExitedRevert,
     ExitedNotSupported(NotSupportedError),
 }
 
@@ -85,12 +96,12 @@ pub trait VM {
     fn step(&mut self) -> Result<(), RequireError>;
     /// Run instructions until it reaches a `RequireError` or
     /// exits. If this function succeeds, the VM status can only be
-    /// either `ExitedOk` or `ExitedErr`.
+    /// `ExitedOk`, `ExitedErr` or `ExitedRevert`.
     fn fire(&mut self) -> Result<(), RequireError> {
         loop {
             match self.status() {
                 VMStatus::Running => self.step()?,
-                VMStatus::ExitedOk | VMStatus::ExitedErr(_) |
+                VMStatus::ExitedOk | VMStatus::ExitedErr(_) | VMStatus::ExitedRevert |
                 VMStatus::ExitedNotSupported(_) => return Ok(()),
             }
         }
@@ -197,6 +208,7 @@ impl<M: Memory + Default, P: Patch> VM for ContextVM<M, P> {
             MachineStatus::Running | MachineStatus::InvokeCreate(_) | MachineStatus::InvokeCall(_, _) => VMStatus::Running,
             MachineStatus::ExitedOk => VMStatus::ExitedOk,
             MachineStatus::ExitedErr(err) => VMStatus::ExitedErr(err.into()),
+            MachineStatus::ExitedRevert => VMStatus::ExitedRevert,
             MachineStatus::ExitedNotSupported(err) => VMStatus::ExitedNotSupported(err),
         }
     }
@@ -206,7 +218,7 @@ impl<M: Memory + Default, P: Patch> VM for ContextVM<M, P> {
             MachineStatus::Running => {
                 self.machines.last_mut().unwrap().step()
             },
-            MachineStatus::ExitedOk | MachineStatus::ExitedErr(_) => {
+            MachineStatus::ExitedOk | MachineStatus::ExitedErr(_) | MachineStatus::ExitedRevert => {
                 if self.machines.len() == 0 {
                     panic!()
                 } else if self.machines.len() == 1 {
@@ -241,7 +253,7 @@ impl<M: Memory + Default, P: Patch> VM for ContextVM<M, P> {
         loop {
             match self.status() {
                 VMStatus::Running => self.step()?,
-                VMStatus::ExitedOk | VMStatus::ExitedErr(_) |
+                VMStatus::ExitedOk | VMStatus::ExitedErr(_) | VMStatus::ExitedRevert |
                 VMStatus::ExitedNotSupported(_) => return Ok(()),
             }
         }
@@ -1,6 +1,6 @@
 //! Parameters used by the VM.
 
-use bigint::{U256, Address, Gas};
+use bigint::{U256, U512, H256, Address, Gas};
 use block::Header;
 
 #[derive(Debug, Clone)]
@@ -15,9 +15,49 @@ pub struct HeaderParams {
     /// Difficulty of the block.
     pub difficulty: U256,
     /// Total block gas limit.
-    pub gas_limit: Gas
+    pub gas_limit: Gas,
+    /// Base fee per gas of the block (EIP-1559). Zero before it is
+    /// introduced.
+    pub base_fee: Gas,
+    /// Excess blob gas of the block (EIP-4844). Zero before it is
+    /// introduced.
+    pub excess_blob_gas: Gas,
+}
+
+/// Minimum fee per blob gas (EIP-4844).
+const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Controls the maximum rate of change of the blob base fee
+/// (EIP-4844).
+const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+
+/// Approximate `factor * e ** (numerator / denominator)` using
+/// Taylor expansion, as specified by EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let numerator = U512::from(numerator);
+    let denominator = U512::from(denominator);
+    let mut i = U512::one();
+    let mut output = U512::zero();
+    let mut numerator_accum = U512::from(factor) * denominator;
+    while !numerator_accum.is_zero() {
+        output = output + numerator_accum;
+        numerator_accum = (numerator_accum * numerator) / (denominator * i);
+        i = i + U512::one();
+    }
+    (output / denominator).into()
+}
+
+impl HeaderParams {
+    /// Fee per blob gas of the block, derived from its excess blob
+    /// gas (EIP-4844).
+    pub fn blob_base_fee(&self) -> Gas {
+        fake_exponential(U256::from(MIN_BLOB_BASE_FEE), self.excess_blob_gas.into(),
+                         U256::from(BLOB_BASE_FEE_UPDATE_FRACTION)).into()
+    }
 }
 
+/// `Header` carries neither the base fee nor the excess blob gas, so
+/// both are left zero. Set them on the result for blocks after London
+/// or Cancun.
 impl<'a> From<&'a Header> for HeaderParams {
     fn from(val: &'a Header) -> HeaderParams {
         HeaderParams {
@@ -26,6 +66,8 @@ impl<'a> From<&'a Header> for HeaderParams {
             number: val.number,
             difficulty: val.difficulty,
             gas_limit: val.gas_limit,
+            base_fee: Gas::zero(),
+            excess_blob_gas: Gas::zero(),
         }
     }
 }
@@ -54,6 +96,11 @@ pub struct Context {
     pub apprent_value: U256,
     /// Whether this represents a system call.
     pub is_system: bool,
+    /// Whether this runtime is static, in which state modifications
+    /// are not allowed.
+    pub is_static: bool,
+    /// Versioned hashes of the blobs of the transaction (EIP-4844).
+    pub blob_hashes: Vec<H256>,
 }
 
 pub use block::Log;
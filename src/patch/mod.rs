@@ -7,7 +7,21 @@ pub use self::precompiled::*;
 
 use std::ops::Deref;
 use std::str::FromStr;
+use std::marker::PhantomData;
 use bigint::{Address, Gas};
+use block::{SignaturePatch, ClassicSignaturePatch};
+
+/// Gas metering of the SSTORE opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SstoreGasMetering {
+    /// Cost only depends on the current and the new value.
+    Legacy,
+    /// Net gas metering defined in EIP-1283.
+    EIP1283,
+    /// Net gas metering defined in EIP-2200, which also fails the
+    /// SSTORE if no more than the call stipend is left.
+    EIP2200,
+}
 
 /// Represents different block range context.
 pub trait Patch {
@@ -15,10 +29,14 @@ pub trait Patch {
     fn callstack_limit() -> usize;
     /// Gas paid for extcode.
     fn gas_extcode() -> Gas;
+    /// Gas paid for EXTCODEHASH opcode.
+    fn gas_extcodehash() -> Gas;
     /// Gas paid for BALANCE opcode.
     fn gas_balance() -> Gas;
     /// Gas paid for SLOAD opcode.
     fn gas_sload() -> Gas;
+    /// Gas metering model of SSTORE opcode.
+    fn sstore_gas_metering() -> SstoreGasMetering;
     /// Gas paid for SUICIDE opcode.
     fn gas_suicide() -> Gas;
     /// Gas paid for SUICIDE opcode when it hits a new account.
@@ -27,13 +45,87 @@ pub trait Patch {
     fn gas_call() -> Gas;
     /// Gas paid for EXP opcode for every byte.
     fn gas_expbyte() -> Gas;
+    /// Gas paid for every transaction.
+    fn gas_transaction() -> Gas;
     /// Gas paid for a contract creation transaction.
     fn gas_transaction_create() -> Gas;
+    /// Gas paid for every 32-byte word of the init code of a
+    /// contract creation (EIP-3860).
+    fn gas_initcode_word() -> Gas;
+    /// Gas paid for every zero byte of the transaction data.
+    fn gas_transaction_zero_data() -> Gas;
+    /// Gas paid for every non-zero byte of the transaction data.
+    fn gas_transaction_non_zero_data() -> Gas;
+    /// Gas paid for every address in the access list of the
+    /// transaction.
+    fn gas_access_list_address() -> Gas;
+    /// Gas paid for every storage key in the access list of the
+    /// transaction.
+    fn gas_access_list_storage_key() -> Gas;
+    /// Gas refunded for clearing a storage slot.
+    fn gas_refund_sstore_clears() -> Gas;
+    /// Gas refunded for SUICIDE.
+    fn gas_refund_suicide() -> Gas;
+    /// Refunded gas is capped at the used gas divided by this
+    /// quotient.
+    fn max_refund_quotient() -> usize;
+    /// Chain ID used by EIP-155 replay-protected signatures and the
+    /// CHAINID opcode. `None` if replay-protected transactions are
+    /// not accepted.
+    fn chain_id() -> Option<u64>;
     /// Whether to force code deposit even if it does not have enough
     /// gas.
     fn force_code_deposit() -> bool;
+    /// Maximum size of the code deposited by a contract creation, if
+    /// any.
+    fn max_code_size() -> Option<usize>;
+    /// Maximum size of the init code of a contract creation, if any.
+    fn max_initcode_size() -> Option<usize>;
+    /// Whether deposited code starting with 0xEF is rejected
+    /// (EIP-3541).
+    fn reject_ef_code() -> bool;
+    /// Whether empty accounts are considered existing. If not, empty
+    /// accounts touched in a transaction are removed after it (EIP-161).
+    fn empty_considered_exists() -> bool;
+    /// Whether a newly created account starts with nonce one instead
+    /// of zero.
+    fn create_increase_nonce() -> bool;
+    /// Whether access list transactions (EIP-2930) are accepted.
+    fn has_access_list_transaction() -> bool;
+    /// Whether blocks have a base fee, which is burnt, with BASEFEE
+    /// opcode and dynamic fee transactions (EIP-1559).
+    fn has_base_fee() -> bool;
+    /// Whether the EVM has PUSH0 opcode (EIP-3855).
+    fn has_push0() -> bool;
+    /// Whether the EVM has TLOAD and TSTORE opcodes (EIP-1153).
+    fn has_transient_storage() -> bool;
+    /// Whether the EVM has MCOPY opcode (EIP-5656).
+    fn has_mcopy() -> bool;
+    /// Whether blob transactions, BLOBHASH and BLOBBASEFEE opcodes
+    /// are supported (EIP-4844).
+    fn has_blob_transaction() -> bool;
     /// Whether the EVM has DELEGATECALL opcode.
     fn has_delegate_call() -> bool;
+    /// Whether the EVM has STATICCALL opcode.
+    fn has_static_call() -> bool;
+    /// Whether the EVM has CREATE2 opcode.
+    fn has_create2() -> bool;
+    /// Whether the EVM has EXTCODEHASH opcode.
+    fn has_extcodehash() -> bool;
+    /// Whether the EVM has REVERT opcode.
+    fn has_revert() -> bool;
+    /// Whether the EVM has RETURNDATASIZE and RETURNDATACOPY opcodes.
+    fn has_return_data() -> bool;
+    /// Whether the EVM has SHL, SHR and SAR opcodes.
+    fn has_bitwise_shifting() -> bool;
+    /// Whether the EVM has CHAINID opcode. It is only available if
+    /// the patch also has a chain ID.
+    fn has_chain_id() -> bool;
+    /// Whether the EVM has SELFBALANCE opcode.
+    fn has_self_balance() -> bool;
+    /// Whether accessed addresses and storage keys are tracked, and
+    /// cold accesses charged extra (EIP-2929).
+    fn has_access_tracking() -> bool;
     /// Whether to throw out of gas error when
     /// CALL/CALLCODE/DELEGATECALL requires more than maximum amount
     /// of gas.
@@ -48,8 +140,21 @@ pub trait Patch {
     fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<Precompiled>)];
 }
 
-lazy_static! {
-    static ref ETC_PRECOMPILEDS: [(Address, Option<&'static [u8]>, Box<Precompiled>); 4] = [
+/// Entry of a precompiled contract table, see `Patch::precompileds`.
+type PrecompiledEntry = (Address, Option<&'static [u8]>, Box<dyn Precompiled>);
+
+/// Replace the precompiled contract at `address` in the table, or add
+/// it if there is none yet.
+fn set_precompiled(precompileds: &mut Vec<PrecompiledEntry>, address: Address,
+                   precompiled: Box<dyn Precompiled>) {
+    match precompileds.iter_mut().find(|entry| entry.0 == address) {
+        Some(entry) => entry.2 = precompiled,
+        None => precompileds.push((address, None, precompiled)),
+    }
+}
+
+fn etc_precompileds() -> Vec<PrecompiledEntry> {
+    vec![
         (Address::from_str("0x0000000000000000000000000000000000000001").unwrap(),
          None,
          Box::new(ECRECPrecompiled)),
@@ -62,7 +167,74 @@ lazy_static! {
         (Address::from_str("0x0000000000000000000000000000000000000004").unwrap(),
          None,
          Box::new(IDPrecompiled)),
-    ];
+    ]
+}
+
+fn byzantium_precompileds() -> Vec<PrecompiledEntry> {
+    let mut precompileds = etc_precompileds();
+    precompileds.push((Address::from_str("0x0000000000000000000000000000000000000005").unwrap(),
+                       None,
+                       Box::new(ModexpPrecompiled(ModexpPricing::EIP198))));
+    precompileds.push((Address::from_str("0x0000000000000000000000000000000000000006").unwrap(),
+                       None,
+                       Box::new(BN128AddPrecompiled(BN128Pricing::Byzantium))));
+    precompileds.push((Address::from_str("0x0000000000000000000000000000000000000007").unwrap(),
+                       None,
+                       Box::new(BN128MulPrecompiled(BN128Pricing::Byzantium))));
+    precompileds.push((Address::from_str("0x0000000000000000000000000000000000000008").unwrap(),
+                       None,
+                       Box::new(BN128PairingPrecompiled(BN128Pricing::Byzantium))));
+    precompileds
+}
+
+fn istanbul_precompileds() -> Vec<PrecompiledEntry> {
+    let mut precompileds = byzantium_precompileds();
+    // Repriced alt_bn128 operations (EIP-1108).
+    set_precompiled(&mut precompileds,
+                    Address::from_str("0x0000000000000000000000000000000000000006").unwrap(),
+                    Box::new(BN128AddPrecompiled(BN128Pricing::EIP1108)));
+    set_precompiled(&mut precompileds,
+                    Address::from_str("0x0000000000000000000000000000000000000007").unwrap(),
+                    Box::new(BN128MulPrecompiled(BN128Pricing::EIP1108)));
+    set_precompiled(&mut precompileds,
+                    Address::from_str("0x0000000000000000000000000000000000000008").unwrap(),
+                    Box::new(BN128PairingPrecompiled(BN128Pricing::EIP1108)));
+    // BLAKE2 F compression function (EIP-152).
+    set_precompiled(&mut precompileds,
+                    Address::from_str("0x0000000000000000000000000000000000000009").unwrap(),
+                    Box::new(Blake2FPrecompiled));
+    precompileds
+}
+
+fn berlin_precompileds() -> Vec<PrecompiledEntry> {
+    let mut precompileds = istanbul_precompileds();
+    // Repriced MODEXP (EIP-2565).
+    set_precompiled(&mut precompileds,
+                    Address::from_str("0x0000000000000000000000000000000000000005").unwrap(),
+                    Box::new(ModexpPrecompiled(ModexpPricing::EIP2565)));
+    precompileds
+}
+
+fn cancun_precompileds() -> Vec<PrecompiledEntry> {
+    let mut precompileds = berlin_precompileds();
+    precompileds.push((Address::from_str("0x000000000000000000000000000000000000000a").unwrap(),
+                       None,
+                       Box::new(KZGPointEvaluationPrecompiled)));
+    precompileds
+}
+
+lazy_static! {
+    static ref ETC_PRECOMPILEDS: Vec<PrecompiledEntry> = etc_precompileds();
+    static ref BYZANTIUM_PRECOMPILEDS: Vec<PrecompiledEntry> = byzantium_precompileds();
+    static ref ISTANBUL_PRECOMPILEDS: Vec<PrecompiledEntry> = istanbul_precompileds();
+    static ref BERLIN_PRECOMPILEDS: Vec<PrecompiledEntry> = berlin_precompileds();
+    static ref CANCUN_PRECOMPILEDS: Vec<PrecompiledEntry> = cancun_precompileds();
+}
+
+/// EIP155 Ethereum Foundation chain.
+pub struct FoundationSignaturePatch;
+impl SignaturePatch for FoundationSignaturePatch {
+    fn chain_id() -> Option<u64> { Some(1) }
 }
 
 /// Frontier patch.
@@ -70,15 +242,47 @@ pub struct FrontierPatch;
 impl Patch for FrontierPatch {
     fn callstack_limit() -> usize { 1024 }
     fn gas_extcode() -> Gas { Gas::from(20usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
     fn gas_balance() -> Gas { Gas::from(20usize) }
     fn gas_sload() -> Gas { Gas::from(50usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
     fn gas_suicide() -> Gas { Gas::from(0usize) }
     fn gas_suicide_new_account() -> Gas { Gas::from(0usize) }
     fn gas_call() -> Gas { Gas::from(40usize) }
     fn gas_expbyte() -> Gas { Gas::from(10usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
     fn gas_transaction_create() -> Gas { Gas::from(0usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { None }
     fn force_code_deposit() -> bool { true }
+    fn max_code_size() -> Option<usize> { None }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { true }
+    fn create_increase_nonce() -> bool { false }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
     fn has_delegate_call() -> bool { false }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
     fn err_on_call_with_more_gas() -> bool { true }
     fn call_create_l64_after_gas() -> bool { false }
     fn memory_limit() -> usize { usize::max_value() }
@@ -91,15 +295,47 @@ pub struct HomesteadPatch;
 impl Patch for HomesteadPatch {
     fn callstack_limit() -> usize { 1024 }
     fn gas_extcode() -> Gas { Gas::from(20usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
     fn gas_balance() -> Gas { Gas::from(20usize) }
     fn gas_sload() -> Gas { Gas::from(50usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
     fn gas_suicide() -> Gas { Gas::from(0usize) }
     fn gas_suicide_new_account() -> Gas { Gas::from(0usize) }
     fn gas_call() -> Gas { Gas::from(40usize) }
     fn gas_expbyte() -> Gas { Gas::from(10usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
     fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { None }
     fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { None }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { true }
+    fn create_increase_nonce() -> bool { false }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
     fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
     fn err_on_call_with_more_gas() -> bool { true }
     fn call_create_l64_after_gas() -> bool { false }
     fn memory_limit() -> usize { usize::max_value() }
@@ -112,15 +348,47 @@ pub struct VMTestPatch;
 impl Patch for VMTestPatch {
     fn callstack_limit() -> usize { 2 }
     fn gas_extcode() -> Gas { Gas::from(20usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
     fn gas_balance() -> Gas { Gas::from(20usize) }
     fn gas_sload() -> Gas { Gas::from(50usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
     fn gas_suicide() -> Gas { Gas::from(0usize) }
     fn gas_suicide_new_account() -> Gas { Gas::from(0usize) }
     fn gas_call() -> Gas { Gas::from(40usize) }
     fn gas_expbyte() -> Gas { Gas::from(10usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
     fn gas_transaction_create() -> Gas { Gas::from(0usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { None }
     fn force_code_deposit() -> bool { true }
+    fn max_code_size() -> Option<usize> { None }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { true }
+    fn create_increase_nonce() -> bool { false }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
     fn has_delegate_call() -> bool { false }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
     fn err_on_call_with_more_gas() -> bool { true }
     fn call_create_l64_after_gas() -> bool { false }
     fn memory_limit() -> usize { usize::max_value() }
@@ -133,15 +401,47 @@ pub struct EIP150Patch;
 impl Patch for EIP150Patch {
     fn callstack_limit() -> usize { 1024 }
     fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
     fn gas_balance() -> Gas { Gas::from(400usize) }
     fn gas_sload() -> Gas { Gas::from(200usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
     fn gas_suicide() -> Gas { Gas::from(5000usize) }
     fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
     fn gas_call() -> Gas { Gas::from(700usize) }
     fn gas_expbyte() -> Gas { Gas::from(10usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
     fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { None }
     fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { None }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { true }
+    fn create_increase_nonce() -> bool { false }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
     fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
     fn err_on_call_with_more_gas() -> bool { false }
     fn call_create_l64_after_gas() -> bool { true }
     fn memory_limit() -> usize { usize::max_value() }
@@ -149,23 +449,481 @@ impl Patch for EIP150Patch {
         ETC_PRECOMPILEDS.deref() }
 }
 
-/// EIP160 patch.
-pub struct EIP160Patch;
-impl Patch for EIP160Patch {
+/// EIP160 patch. This and all later patches check replay-protected
+/// signatures, and return from CHAINID, the chain ID of the signature
+/// patch `S`, which defaults to Ethereum Classic.
+pub struct EIP160Patch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for EIP160Patch<S> {
     fn callstack_limit() -> usize { 1024 }
     fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
     fn gas_balance() -> Gas { Gas::from(400usize) }
     fn gas_sload() -> Gas { Gas::from(200usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
     fn gas_suicide() -> Gas { Gas::from(5000usize) }
     fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
     fn gas_call() -> Gas { Gas::from(700usize) }
     fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
     fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
     fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { None }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { true }
+    fn create_increase_nonce() -> bool { false }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
     fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
     fn err_on_call_with_more_gas() -> bool { false }
     fn call_create_l64_after_gas() -> bool { true }
     fn memory_limit() -> usize { usize::max_value() }
     fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<Precompiled>)] {
         ETC_PRECOMPILEDS.deref() }
 }
+
+/// Spurious Dragon patch.
+pub struct SpuriousDragonPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for SpuriousDragonPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
+    fn gas_balance() -> Gas { Gas::from(400usize) }
+    fn gas_sload() -> Gas { Gas::from(200usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(700usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { false }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { false }
+    fn has_return_data() -> bool { false }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        ETC_PRECOMPILEDS.deref() }
+}
+
+/// Byzantium patch.
+pub struct ByzantiumPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for ByzantiumPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
+    fn gas_balance() -> Gas { Gas::from(400usize) }
+    fn gas_sload() -> Gas { Gas::from(200usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::Legacy }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(700usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { false }
+    fn has_extcodehash() -> bool { false }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { false }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        BYZANTIUM_PRECOMPILEDS.deref() }
+}
+
+/// Constantinople patch.
+pub struct ConstantinoplePatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for ConstantinoplePatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(400usize) }
+    fn gas_balance() -> Gas { Gas::from(400usize) }
+    fn gas_sload() -> Gas { Gas::from(200usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP1283 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(700usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(68usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { false }
+    fn has_self_balance() -> bool { false }
+    fn has_access_tracking() -> bool { false }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        BYZANTIUM_PRECOMPILEDS.deref() }
+}
+
+/// Istanbul patch.
+pub struct IstanbulPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for IstanbulPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(700usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(700usize) }
+    fn gas_balance() -> Gas { Gas::from(700usize) }
+    fn gas_sload() -> Gas { Gas::from(800usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP2200 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(700usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(16usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(0usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(0usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { false }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { true }
+    fn has_self_balance() -> bool { true }
+    fn has_access_tracking() -> bool { false }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        ISTANBUL_PRECOMPILEDS.deref() }
+}
+
+/// Berlin patch.
+pub struct BerlinPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for BerlinPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(100usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(100usize) }
+    fn gas_balance() -> Gas { Gas::from(100usize) }
+    fn gas_sload() -> Gas { Gas::from(100usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP2200 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(100usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(16usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(2400usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(1900usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(15000usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(24000usize) }
+    fn max_refund_quotient() -> usize { 2 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { false }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { true }
+    fn has_base_fee() -> bool { false }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { true }
+    fn has_self_balance() -> bool { true }
+    fn has_access_tracking() -> bool { true }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        BERLIN_PRECOMPILEDS.deref() }
+}
+
+/// London patch.
+pub struct LondonPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for LondonPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(100usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(100usize) }
+    fn gas_balance() -> Gas { Gas::from(100usize) }
+    fn gas_sload() -> Gas { Gas::from(100usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP2200 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(100usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(0usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(16usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(2400usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(1900usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(4800usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(0usize) }
+    fn max_refund_quotient() -> usize { 5 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { None }
+    fn reject_ef_code() -> bool { true }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { true }
+    fn has_base_fee() -> bool { true }
+    fn has_push0() -> bool { false }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { true }
+    fn has_self_balance() -> bool { true }
+    fn has_access_tracking() -> bool { true }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        BERLIN_PRECOMPILEDS.deref() }
+}
+
+/// Shanghai patch.
+pub struct ShanghaiPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for ShanghaiPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(100usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(100usize) }
+    fn gas_balance() -> Gas { Gas::from(100usize) }
+    fn gas_sload() -> Gas { Gas::from(100usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP2200 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(100usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(2usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(16usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(2400usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(1900usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(4800usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(0usize) }
+    fn max_refund_quotient() -> usize { 5 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { Some(0xc000) }
+    fn reject_ef_code() -> bool { true }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { true }
+    fn has_base_fee() -> bool { true }
+    fn has_push0() -> bool { true }
+    fn has_transient_storage() -> bool { false }
+    fn has_mcopy() -> bool { false }
+    fn has_blob_transaction() -> bool { false }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { true }
+    fn has_self_balance() -> bool { true }
+    fn has_access_tracking() -> bool { true }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        BERLIN_PRECOMPILEDS.deref() }
+}
+
+/// Cancun patch.
+pub struct CancunPatch<S: SignaturePatch = ClassicSignaturePatch>(PhantomData<S>);
+impl<S: SignaturePatch> Patch for CancunPatch<S> {
+    fn callstack_limit() -> usize { 1024 }
+    fn gas_extcode() -> Gas { Gas::from(100usize) }
+    fn gas_extcodehash() -> Gas { Gas::from(100usize) }
+    fn gas_balance() -> Gas { Gas::from(100usize) }
+    fn gas_sload() -> Gas { Gas::from(100usize) }
+    fn sstore_gas_metering() -> SstoreGasMetering { SstoreGasMetering::EIP2200 }
+    fn gas_suicide() -> Gas { Gas::from(5000usize) }
+    fn gas_suicide_new_account() -> Gas { Gas::from(25000usize) }
+    fn gas_call() -> Gas { Gas::from(100usize) }
+    fn gas_expbyte() -> Gas { Gas::from(50usize) }
+    fn gas_transaction() -> Gas { Gas::from(21000usize) }
+    fn gas_transaction_create() -> Gas { Gas::from(32000usize) }
+    fn gas_initcode_word() -> Gas { Gas::from(2usize) }
+    fn gas_transaction_zero_data() -> Gas { Gas::from(4usize) }
+    fn gas_transaction_non_zero_data() -> Gas { Gas::from(16usize) }
+    fn gas_access_list_address() -> Gas { Gas::from(2400usize) }
+    fn gas_access_list_storage_key() -> Gas { Gas::from(1900usize) }
+    fn gas_refund_sstore_clears() -> Gas { Gas::from(4800usize) }
+    fn gas_refund_suicide() -> Gas { Gas::from(0usize) }
+    fn max_refund_quotient() -> usize { 5 }
+    fn chain_id() -> Option<u64> { S::chain_id() }
+    fn force_code_deposit() -> bool { false }
+    fn max_code_size() -> Option<usize> { Some(0x6000) }
+    fn max_initcode_size() -> Option<usize> { Some(0xc000) }
+    fn reject_ef_code() -> bool { true }
+    fn empty_considered_exists() -> bool { false }
+    fn create_increase_nonce() -> bool { true }
+    fn has_access_list_transaction() -> bool { true }
+    fn has_base_fee() -> bool { true }
+    fn has_push0() -> bool { true }
+    fn has_transient_storage() -> bool { true }
+    fn has_mcopy() -> bool { true }
+    fn has_blob_transaction() -> bool { true }
+    fn has_delegate_call() -> bool { true }
+    fn has_static_call() -> bool { true }
+    fn has_create2() -> bool { true }
+    fn has_extcodehash() -> bool { true }
+    fn has_revert() -> bool { true }
+    fn has_return_data() -> bool { true }
+    fn has_bitwise_shifting() -> bool { true }
+    fn has_chain_id() -> bool { true }
+    fn has_self_balance() -> bool { true }
+    fn has_access_tracking() -> bool { true }
+    fn err_on_call_with_more_gas() -> bool { false }
+    fn call_create_l64_after_gas() -> bool { true }
+    fn memory_limit() -> usize { usize::MAX }
+    fn precompileds() -> &'static [(Address, Option<&'static [u8]>, Box<dyn Precompiled>)] {
+        CANCUN_PRECOMPILEDS.deref() }
+}
@@ -1,5 +1,5 @@
-use bigint::Gas;
-use std::cmp::min;
+use bigint::{U256, Gas};
+use std::cmp::{min, max};
 
 use errors::{RuntimeError, OnChainError};
 use sha2::Sha256;
@@ -7,6 +7,10 @@ use sha3::Keccak256;
 use ripemd160::Ripemd160;
 use secp256k1::{SECP256K1, RecoverableSignature, Message, RecoveryId, Error};
 use digest::{Digest, FixedOutput};
+use num_bigint::BigUint;
+use bn::{Fq, Fq2, Fr, G1, G2, Gt, AffineG1, AffineG2, Group, pairing_batch};
+use c_kzg::{ethereum_kzg_settings, Bytes32, Bytes48};
+use typed_transaction::VERSIONED_HASH_VERSION_KZG;
 
 /// Represent a precompiled contract.
 pub trait Precompiled: Sync {
@@ -107,6 +111,397 @@ impl Precompiled for ECRECPrecompiled {
     }
 }
 
+/// Gas pricing formula of the MODEXP precompiled contract.
+pub enum ModexpPricing {
+    /// The original formula defined in EIP-198.
+    EIP198,
+    /// The repriced formula defined in EIP-2565.
+    EIP2565,
+}
+
+/// MODEXP precompiled contract.
+pub struct ModexpPrecompiled(pub ModexpPricing);
+impl Precompiled for ModexpPrecompiled {
+    fn gas(&self, data: &[u8]) -> Gas {
+        let base_len = U256::from(read_padded(data, U256::zero(), 32).as_slice());
+        let exp_len = U256::from(read_padded(data, U256::from(32u64), 32).as_slice());
+        let mod_len = U256::from(read_padded(data, U256::from(64u64), 32).as_slice());
+
+        // Only the first 32 bytes of the exponent are read, so that
+        // huge declared lengths never get allocated.
+        let exp_start = U256::from(96u64) + min(base_len, U256::from(data.len()));
+        let exp_head_len = min(exp_len, U256::from(32u64)).as_usize();
+        let exp_head = U256::from(read_padded(data, exp_start, exp_head_len).as_slice());
+        let exp_head_bits = if exp_head.is_zero() {
+            Gas::zero()
+        } else {
+            Gas::from(exp_head.bits() - 1)
+        };
+        let adjusted_exp_len = if exp_len <= U256::from(32u64) {
+            exp_head_bits
+        } else {
+            Gas::from(8u64) * Gas::from(exp_len - U256::from(32u64)) + exp_head_bits
+        };
+        let iterations = max(adjusted_exp_len, Gas::from(1u64));
+        let max_len = Gas::from(max(base_len, mod_len));
+
+        match self.0 {
+            ModexpPricing::EIP198 => {
+                let complexity = if max_len <= Gas::from(64u64) {
+                    max_len * max_len
+                } else if max_len <= Gas::from(1024u64) {
+                    max_len * max_len / Gas::from(4u64) + Gas::from(96u64) * max_len -
+                        Gas::from(3072u64)
+                } else {
+                    max_len * max_len / Gas::from(16u64) + Gas::from(480u64) * max_len -
+                        Gas::from(199680u64)
+                };
+                complexity * iterations / Gas::from(20u64)
+            },
+            ModexpPricing::EIP2565 => {
+                let words = gas_div_ceil(max_len, Gas::from(8u64));
+                max(Gas::from(200u64), words * words * iterations / Gas::from(3u64))
+            },
+        }
+    }
+
+    fn step(&self, data: &[u8]) -> Vec<u8> {
+        let mod_len = U256::from(read_padded(data, U256::from(64u64), 32).as_slice());
+        if mod_len.is_zero() {
+            return Vec::new();
+        }
+
+        // Lengths here are bounded by the gas paid.
+        let base_len = U256::from(read_padded(data, U256::zero(), 32).as_slice()).as_usize();
+        let exp_len = U256::from(read_padded(data, U256::from(32u64), 32).as_slice()).as_usize();
+        let mod_len = mod_len.as_usize();
+
+        let base = BigUint::from_bytes_be(
+            &read_padded(data, U256::from(96u64), base_len));
+        let exp = BigUint::from_bytes_be(
+            &read_padded(data, U256::from(96 + base_len), exp_len));
+        let modulus = BigUint::from_bytes_be(
+            &read_padded(data, U256::from(96 + base_len + exp_len), mod_len));
+
+        let mut ret = vec![0u8; mod_len];
+        if modulus != BigUint::from(0u32) {
+            let value = base.modpow(&exp, &modulus).to_bytes_be();
+            let start = mod_len - value.len();
+            ret[start..].copy_from_slice(&value);
+        }
+        ret
+    }
+}
+
+/// Gas pricing of the alt_bn128 precompiled contracts.
+pub enum BN128Pricing {
+    /// The original pricing defined in EIP-196 and EIP-197.
+    Byzantium,
+    /// The reduced pricing defined in EIP-1108.
+    EIP1108,
+}
+
+/// BN128 point addition precompiled contract.
+pub struct BN128AddPrecompiled(pub BN128Pricing);
+impl Precompiled for BN128AddPrecompiled {
+    fn gas(&self, _: &[u8]) -> Gas {
+        match self.0 {
+            BN128Pricing::Byzantium => Gas::from(500u64),
+            BN128Pricing::EIP1108 => Gas::from(150u64),
+        }
+    }
+
+    fn gas_and_step(&self, data: &[u8], gas_limit: Gas) -> Result<(Gas, Vec<u8>), RuntimeError> {
+        let gas = self.gas(data);
+        if gas > gas_limit {
+            return Err(RuntimeError::OnChain(OnChainError::EmptyGas));
+        }
+
+        let data = read_padded(data, U256::zero(), 128);
+        let p1 = read_bn128_g1(&data[0..64])?;
+        let p2 = read_bn128_g1(&data[64..128])?;
+        Ok((gas, write_bn128_g1(p1 + p2)))
+    }
+}
+
+/// BN128 scalar multiplication precompiled contract.
+pub struct BN128MulPrecompiled(pub BN128Pricing);
+impl Precompiled for BN128MulPrecompiled {
+    fn gas(&self, _: &[u8]) -> Gas {
+        match self.0 {
+            BN128Pricing::Byzantium => Gas::from(40000u64),
+            BN128Pricing::EIP1108 => Gas::from(6000u64),
+        }
+    }
+
+    fn gas_and_step(&self, data: &[u8], gas_limit: Gas) -> Result<(Gas, Vec<u8>), RuntimeError> {
+        let gas = self.gas(data);
+        if gas > gas_limit {
+            return Err(RuntimeError::OnChain(OnChainError::EmptyGas));
+        }
+
+        let data = read_padded(data, U256::zero(), 96);
+        let p = read_bn128_g1(&data[0..64])?;
+        let s = Fr::from_slice(&data[64..96])
+            .map_err(|_| RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput))?;
+        Ok((gas, write_bn128_g1(p * s)))
+    }
+}
+
+/// BN128 pairing check precompiled contract.
+pub struct BN128PairingPrecompiled(pub BN128Pricing);
+impl Precompiled for BN128PairingPrecompiled {
+    fn gas(&self, data: &[u8]) -> Gas {
+        let pairs = Gas::from(data.len() / 192);
+        match self.0 {
+            BN128Pricing::Byzantium =>
+                Gas::from(100000u64) + Gas::from(80000u64) * pairs,
+            BN128Pricing::EIP1108 =>
+                Gas::from(45000u64) + Gas::from(34000u64) * pairs,
+        }
+    }
+
+    fn gas_and_step(&self, data: &[u8], gas_limit: Gas) -> Result<(Gas, Vec<u8>), RuntimeError> {
+        let gas = self.gas(data);
+        if gas > gas_limit {
+            return Err(RuntimeError::OnChain(OnChainError::EmptyGas));
+        }
+        if data.len() % 192 != 0 {
+            return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput));
+        }
+
+        let mut pairs = Vec::new();
+        for chunk in data.chunks(192) {
+            let p = read_bn128_g1(&chunk[0..64])?;
+            let q = read_bn128_g2(&chunk[64..192])?;
+            if !p.is_zero() && !q.is_zero() {
+                pairs.push((p, q));
+            }
+        }
+
+        let success = pairs.is_empty() || pairing_batch(&pairs) == Gt::one();
+        let mut ret = [0u8; 32];
+        if success {
+            ret[31] = 1;
+        }
+        Ok((gas, ret.as_ref().into()))
+    }
+}
+
+/// BLAKE2b F compression function precompiled contract (EIP-152).
+pub struct Blake2FPrecompiled;
+impl Precompiled for Blake2FPrecompiled {
+    fn gas(&self, data: &[u8]) -> Gas {
+        if data.len() != BLAKE2F_INPUT_LEN {
+            return Gas::zero();
+        }
+        Gas::from(read_u32_be(&data[0..4]) as u64)
+    }
+
+    fn gas_and_step(&self, data: &[u8], gas_limit: Gas) -> Result<(Gas, Vec<u8>), RuntimeError> {
+        if data.len() != BLAKE2F_INPUT_LEN {
+            return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput));
+        }
+        let gas = self.gas(data);
+        if gas > gas_limit {
+            return Err(RuntimeError::OnChain(OnChainError::EmptyGas));
+        }
+        let last = match data[212] {
+            0 => false,
+            1 => true,
+            _ => return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput)),
+        };
+
+        let rounds = read_u32_be(&data[0..4]);
+        let mut h = [0u64; 8];
+        let mut m = [0u64; 16];
+        for i in 0..8 {
+            h[i] = read_u64_le(&data[(4 + i * 8)..(12 + i * 8)]);
+        }
+        for i in 0..16 {
+            m[i] = read_u64_le(&data[(68 + i * 8)..(76 + i * 8)]);
+        }
+        let t = [read_u64_le(&data[196..204]), read_u64_le(&data[204..212])];
+
+        blake2_f(rounds, &mut h, &m, t, last);
+
+        let mut ret = Vec::with_capacity(64);
+        for word in h.iter() {
+            for i in 0..8 {
+                ret.push((word >> (i * 8)) as u8);
+            }
+        }
+        Ok((gas, ret))
+    }
+}
+
+/// KZG point evaluation precompiled contract (EIP-4844). Proofs are
+/// verified against the Ethereum mainnet trusted setup.
+pub struct KZGPointEvaluationPrecompiled;
+impl Precompiled for KZGPointEvaluationPrecompiled {
+    fn gas(&self, _: &[u8]) -> Gas {
+        Gas::from(50000u64)
+    }
+
+    fn gas_and_step(&self, data: &[u8], gas_limit: Gas) -> Result<(Gas, Vec<u8>), RuntimeError> {
+        if data.len() != KZG_POINT_EVALUATION_INPUT_LEN {
+            return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput));
+        }
+        let gas = self.gas(data);
+        if gas > gas_limit {
+            return Err(RuntimeError::OnChain(OnChainError::EmptyGas));
+        }
+
+        let commitment = &data[96..144];
+        let mut sha2 = Sha256::default();
+        sha2.input(commitment);
+        let mut versioned_hash = sha2.fixed_result();
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        if versioned_hash[..] != data[0..32] {
+            return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput));
+        }
+
+        let verified = match (Bytes48::from_bytes(commitment),
+                              Bytes32::from_bytes(&data[32..64]),
+                              Bytes32::from_bytes(&data[64..96]),
+                              Bytes48::from_bytes(&data[144..192])) {
+            (Ok(commitment), Ok(z), Ok(y), Ok(proof)) => {
+                ethereum_kzg_settings(0).verify_kzg_proof(&commitment, &z, &y, &proof)
+            },
+            _ => return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput)),
+        };
+        match verified {
+            Ok(true) => (),
+            _ => return Err(RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput)),
+        }
+
+        let mut ret = [0u8; 64];
+        U256::from(FIELD_ELEMENTS_PER_BLOB).to_big_endian(&mut ret[0..32]);
+        ret[32..64].copy_from_slice(&BLS_MODULUS);
+        Ok((gas, ret.as_ref().into()))
+    }
+}
+
+const KZG_POINT_EVALUATION_INPUT_LEN: usize = 192;
+const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+const BLS_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+const BLAKE2F_INPUT_LEN: usize = 213;
+
+const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const BLAKE2B_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+fn blake2_g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn blake2_f(rounds: u32, h: &mut [u64; 8], m: &[u64; 16], t: [u64; 2], last: bool) {
+    let mut v = [0u64; 16];
+    v[0..8].copy_from_slice(&h[..]);
+    v[8..16].copy_from_slice(&BLAKE2B_IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if last {
+        v[14] = !v[14];
+    }
+
+    for i in 0..(rounds as usize) {
+        let s = &BLAKE2B_SIGMA[i % 10];
+        blake2_g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        blake2_g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        blake2_g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        blake2_g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        blake2_g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        blake2_g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        blake2_g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        blake2_g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+fn read_u32_be(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |acc, &b| (acc << 8) | b as u32)
+}
+
+fn read_u64_le(data: &[u8]) -> u64 {
+    data.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+fn read_bn128_fq(data: &[u8]) -> Result<Fq, RuntimeError> {
+    Fq::from_slice(data)
+        .map_err(|_| RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput))
+}
+
+fn read_bn128_g1(data: &[u8]) -> Result<G1, RuntimeError> {
+    let x = read_bn128_fq(&data[0..32])?;
+    let y = read_bn128_fq(&data[32..64])?;
+    if x.is_zero() && y.is_zero() {
+        Ok(G1::zero())
+    } else {
+        AffineG1::new(x, y).map(Into::into)
+            .map_err(|_| RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput))
+    }
+}
+
+fn read_bn128_g2(data: &[u8]) -> Result<G2, RuntimeError> {
+    // Coordinates are encoded with the imaginary part first.
+    let x = Fq2::new(read_bn128_fq(&data[32..64])?, read_bn128_fq(&data[0..32])?);
+    let y = Fq2::new(read_bn128_fq(&data[96..128])?, read_bn128_fq(&data[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        Ok(G2::zero())
+    } else {
+        AffineG2::new(x, y).map(Into::into)
+            .map_err(|_| RuntimeError::OnChain(OnChainError::InvalidPrecompiledInput))
+    }
+}
+
+fn write_bn128_g1(p: G1) -> Vec<u8> {
+    let mut ret = [0u8; 64];
+    if let Some(p) = AffineG1::from_jacobian(p) {
+        p.x().to_big_endian(&mut ret[0..32]).unwrap();
+        p.y().to_big_endian(&mut ret[32..64]).unwrap();
+    }
+    ret.as_ref().into()
+}
+
+fn read_padded(data: &[u8], offset: U256, len: usize) -> Vec<u8> {
+    let mut ret = vec![0u8; len];
+    if offset < U256::from(data.len()) {
+        let offset = offset.as_usize();
+        let available = min(len, data.len() - offset);
+        ret[..available].copy_from_slice(&data[offset..(offset + available)]);
+    }
+    ret
+}
+
 fn gas_div_ceil(a: Gas, b: Gas) -> Gas {
     if a % b == Gas::zero() {
         a / b
@@ -135,3 +530,123 @@ fn kececrec(data: &[u8]) -> Result<[u8; 32], Error> {
 
     Ok(ret)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hexutil::read_hex;
+
+    #[test]
+    fn modexp() {
+        // 3 ^ (p - 2) mod p, from the EIP-198 examples.
+        let data = read_hex("0x0000000000000000000000000000000000000000000000000000000000000001\
+                             0000000000000000000000000000000000000000000000000000000000000020\
+                             0000000000000000000000000000000000000000000000000000000000000020\
+                             03\
+                             fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+                             fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f").unwrap();
+        assert_eq!(ModexpPrecompiled(ModexpPricing::EIP198).gas(&data), Gas::from(13056u64));
+        assert_eq!(ModexpPrecompiled(ModexpPricing::EIP2565).gas(&data), Gas::from(1360u64));
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(ModexpPrecompiled(ModexpPricing::EIP198).step(&data), expected.as_ref());
+
+        // Huge declared lengths are rejected by gas without allocating.
+        let data = read_hex("0x0000000000000000000000000000000000000000000000000000000000000000\
+                             0000000000000000000000000000000000000000000000000000000000000020\
+                             ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap();
+        let precompiled = ModexpPrecompiled(ModexpPricing::EIP198);
+        assert!(precompiled.gas_and_step(&data, Gas::from(u64::max_value())).is_err());
+    }
+    #[test]
+    fn bn128() {
+        // The generator added to itself.
+        let data = read_hex("0x0000000000000000000000000000000000000000000000000000000000000001\
+                             0000000000000000000000000000000000000000000000000000000000000002\
+                             0000000000000000000000000000000000000000000000000000000000000001\
+                             0000000000000000000000000000000000000000000000000000000000000002").unwrap();
+        let expected = read_hex("0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+                                 15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4").unwrap();
+        let (gas, ret) = BN128AddPrecompiled(BN128Pricing::Byzantium)
+            .gas_and_step(&data, Gas::from(500u64)).unwrap();
+        assert_eq!(gas, Gas::from(500u64));
+        assert_eq!(ret, expected);
+
+        // The generator multiplied by two.
+        let data = read_hex("0x0000000000000000000000000000000000000000000000000000000000000001\
+                             0000000000000000000000000000000000000000000000000000000000000002\
+                             0000000000000000000000000000000000000000000000000000000000000002").unwrap();
+        let (gas, ret) = BN128MulPrecompiled(BN128Pricing::EIP1108)
+            .gas_and_step(&data, Gas::from(6000u64)).unwrap();
+        assert_eq!(gas, Gas::from(6000u64));
+        assert_eq!(ret, expected);
+
+        // A point not on the curve.
+        let data = read_hex("0x0000000000000000000000000000000000000000000000000000000000000001\
+                             0000000000000000000000000000000000000000000000000000000000000003").unwrap();
+        assert!(BN128AddPrecompiled(BN128Pricing::EIP1108)
+                .gas_and_step(&data, Gas::from(150u64)).is_err());
+
+        // An empty pairing check succeeds.
+        let (gas, ret) = BN128PairingPrecompiled(BN128Pricing::Byzantium)
+            .gas_and_step(&[], Gas::from(100000u64)).unwrap();
+        assert_eq!(gas, Gas::from(100000u64));
+        assert_eq!(ret[31], 1);
+    }
+
+    #[test]
+    fn blake2f() {
+        // Test vectors from EIP-152: "abc" with 12 rounds.
+        let input = "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+                     d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b\
+                     6162630000000000000000000000000000000000000000000000000000000000\
+                     0000000000000000000000000000000000000000000000000000000000000000\
+                     0000000000000000000000000000000000000000000000000000000000000000\
+                     0000000000000000000000000000000000000000000000000000000000000000\
+                     03000000000000000000000000000000";
+        let data = read_hex(&format!("0x0000000c{}01", input)).unwrap();
+        let expected = read_hex("0xba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+                                 7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap();
+        let (gas, ret) = Blake2FPrecompiled.gas_and_step(&data, Gas::from(12u64)).unwrap();
+        assert_eq!(gas, Gas::from(12u64));
+        assert_eq!(ret, expected);
+
+        let data = read_hex(&format!("0x0000000c{}00", input)).unwrap();
+        let expected = read_hex("0x75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d28752\
+                                 98743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735").unwrap();
+        assert_eq!(Blake2FPrecompiled.gas_and_step(&data, Gas::from(12u64)).unwrap().1, expected);
+
+        // Invalid final block flag, and a truncated input.
+        let data = read_hex(&format!("0x0000000c{}02", input)).unwrap();
+        assert!(Blake2FPrecompiled.gas_and_step(&data, Gas::from(12u64)).is_err());
+        assert!(Blake2FPrecompiled.gas_and_step(&data[1..], Gas::from(12u64)).is_err());
+
+        // Not enough gas for the requested rounds.
+        let data = read_hex(&format!("0x0000000c{}01", input)).unwrap();
+        assert!(Blake2FPrecompiled.gas_and_step(&data, Gas::from(11u64)).is_err());
+    }
+
+    #[test]
+    fn kzg_point_evaluation() {
+        // The commitment to the zero polynomial, which evaluates to
+        // zero everywhere with the proof at infinity.
+        let infinity = "c0000000000000000000000000000000\
+                        0000000000000000000000000000000000000000000000000000000000000000";
+        let hash = "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014";
+        let z = "0000000000000000000000000000000000000000000000000000000000000002";
+        let zero = "0000000000000000000000000000000000000000000000000000000000000000";
+        let one = "0000000000000000000000000000000000000000000000000000000000000001";
+
+        let data = read_hex(&format!("0x{}{}{}{}{}", hash, z, zero, infinity, infinity)).unwrap();
+        let (gas, ret) = KZGPointEvaluationPrecompiled.gas_and_step(&data, Gas::from(50000u64)).unwrap();
+        assert_eq!(gas, Gas::from(50000u64));
+        assert_eq!(ret, read_hex("0x0000000000000000000000000000000000000000000000000000000000001000\
+                                  73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001").unwrap());
+
+        // Wrong evaluation, and a commitment not matching the hash.
+        let data = read_hex(&format!("0x{}{}{}{}{}", hash, z, one, infinity, infinity)).unwrap();
+        assert!(KZGPointEvaluationPrecompiled.gas_and_step(&data, Gas::from(50000u64)).is_err());
+        let data = read_hex(&format!("0x{}{}{}{}{}", one, z, zero, infinity, infinity)).unwrap();
+        assert!(KZGPointEvaluationPrecompiled.gas_and_step(&data, Gas::from(50000u64)).is_err());
+    }
+}
@@ -14,12 +14,13 @@ use super::errors::OnChainError;
 pub enum Instruction {
     STOP, ADD, MUL, SUB, DIV, SDIV, MOD, SMOD, ADDMOD, MULMOD, EXP,
     SIGNEXTEND, LT, GT, SLT, SGT, EQ, ISZERO, AND, OR, XOR, NOT, BYTE,
+    SHL, SHR, SAR,
     SHA3, ADDRESS, BALANCE, ORIGIN, CALLER, CALLVALUE, CALLDATALOAD,
     CALLDATASIZE, CALLDATACOPY, CODESIZE, CODECOPY, GASPRICE,
-    EXTCODESIZE, EXTCODECOPY, BLOCKHASH, COINBASE, TIMESTAMP, NUMBER,
-    DIFFICULTY, GASLIMIT, POP, MLOAD, MSTORE, MSTORE8, SLOAD, SSTORE,
-    JUMP, JUMPI, PC, MSIZE, GAS, JUMPDEST, CREATE, CALL, CALLCODE,
-    RETURN, DELEGATECALL, SUICIDE,
+    EXTCODESIZE, EXTCODECOPY, RETURNDATASIZE, RETURNDATACOPY, EXTCODEHASH, BLOCKHASH, COINBASE, TIMESTAMP, NUMBER,
+    DIFFICULTY, GASLIMIT, CHAINID, SELFBALANCE, BASEFEE, BLOBHASH, BLOBBASEFEE, POP, MLOAD, MSTORE, MSTORE8, SLOAD, SSTORE,
+    JUMP, JUMPI, PC, MSIZE, GAS, JUMPDEST, TLOAD, TSTORE, MCOPY, PUSH0, CREATE, CALL, CALLCODE,
+    RETURN, DELEGATECALL, CREATE2, STATICCALL, REVERT, SUICIDE,
 
     PUSH(M256),
     DUP(usize),
@@ -172,6 +173,27 @@ impl<P: Patch> PC<P> {
             Opcode::XOR => Instruction::XOR,
             Opcode::NOT => Instruction::NOT,
             Opcode::BYTE => Instruction::BYTE,
+            Opcode::SHL => {
+                if P::has_bitwise_shifting() {
+                    Instruction::SHL
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::SHR => {
+                if P::has_bitwise_shifting() {
+                    Instruction::SHR
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::SAR => {
+                if P::has_bitwise_shifting() {
+                    Instruction::SAR
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
 
             Opcode::SHA3 => Instruction::SHA3,
 
@@ -188,6 +210,27 @@ impl<P: Patch> PC<P> {
             Opcode::GASPRICE => Instruction::GASPRICE,
             Opcode::EXTCODESIZE => Instruction::EXTCODESIZE,
             Opcode::EXTCODECOPY => Instruction::EXTCODECOPY,
+            Opcode::RETURNDATASIZE => {
+                if P::has_return_data() {
+                    Instruction::RETURNDATASIZE
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::RETURNDATACOPY => {
+                if P::has_return_data() {
+                    Instruction::RETURNDATACOPY
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::EXTCODEHASH => {
+                if P::has_extcodehash() {
+                    Instruction::EXTCODEHASH
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
 
             Opcode::BLOCKHASH => Instruction::BLOCKHASH,
             Opcode::COINBASE => Instruction::COINBASE,
@@ -195,6 +238,41 @@ impl<P: Patch> PC<P> {
             Opcode::NUMBER => Instruction::NUMBER,
             Opcode::DIFFICULTY => Instruction::DIFFICULTY,
             Opcode::GASLIMIT => Instruction::GASLIMIT,
+            Opcode::CHAINID => {
+                if P::has_chain_id() && P::chain_id().is_some() {
+                    Instruction::CHAINID
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::SELFBALANCE => {
+                if P::has_self_balance() {
+                    Instruction::SELFBALANCE
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::BASEFEE => {
+                if P::has_base_fee() {
+                    Instruction::BASEFEE
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::BLOBHASH => {
+                if P::has_blob_transaction() {
+                    Instruction::BLOBHASH
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::BLOBBASEFEE => {
+                if P::has_blob_transaction() {
+                    Instruction::BLOBBASEFEE
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
 
             Opcode::POP => Instruction::POP,
             Opcode::MLOAD => Instruction::MLOAD,
@@ -208,6 +286,34 @@ impl<P: Patch> PC<P> {
             Opcode::MSIZE => Instruction::MSIZE,
             Opcode::GAS => Instruction::GAS,
             Opcode::JUMPDEST => Instruction::JUMPDEST,
+            Opcode::TLOAD => {
+                if P::has_transient_storage() {
+                    Instruction::TLOAD
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::TSTORE => {
+                if P::has_transient_storage() {
+                    Instruction::TSTORE
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::MCOPY => {
+                if P::has_mcopy() {
+                    Instruction::MCOPY
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::PUSH0 => {
+                if P::has_push0() {
+                    Instruction::PUSH0
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
 
             Opcode::PUSH(v) => {
                 let param = self.read_bytes(position + 1, v)?;
@@ -229,6 +335,27 @@ impl<P: Patch> PC<P> {
                     return Err(OnChainError::InvalidOpcode);
                 }
             },
+            Opcode::CREATE2 => {
+                if P::has_create2() {
+                    Instruction::CREATE2
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::STATICCALL => {
+                if P::has_static_call() {
+                    Instruction::STATICCALL
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
+            Opcode::REVERT => {
+                if P::has_revert() {
+                    Instruction::REVERT
+                } else {
+                    return Err(OnChainError::InvalidOpcode);
+                }
+            },
 
             Opcode::INVALID => {
                 return Err(OnChainError::InvalidOpcode);
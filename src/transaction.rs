@@ -10,10 +10,7 @@ use super::{State, Machine, Context, ContextVM, VM, AccountState,
             BlockhashState, Patch, HeaderParams, Memory, VMStatus,
             AccountCommitment, Log, AccountChange, MachineStatus};
 use block::{Transaction, TransactionAction};
-
-const G_TXDATAZERO: usize = 4;
-const G_TXDATANONZERO: usize = 68;
-const G_TRANSACTION: usize = 21000;
+use typed_transaction::{TypedTransaction, AccessListItem, VERSIONED_HASH_VERSION_KZG};
 
 macro_rules! system_address {
     () => {
@@ -21,6 +18,12 @@ macro_rules! system_address {
     }
 }
 
+/// Blob gas used by each blob of a transaction (EIP-4844).
+const GAS_PER_BLOB: u64 = 131072;
+/// Maximum blob gas of a block, and therefore of a single
+/// transaction (EIP-4844).
+const MAX_BLOB_GAS_PER_BLOCK: u64 = 786432;
+
 /// ## About SYSTEM transaction
 ///
 /// SYSTEM transaction in Ethereum is something that cannot be
@@ -37,7 +40,8 @@ pub struct ValidTransaction {
     /// Caller of this transaction. If caller is None, then this is a
     /// SYSTEM transaction.
     pub caller: Option<Address>,
-    /// Gas price of this transaction.
+    /// Gas price of this transaction. For a dynamic fee transaction,
+    /// this is the maximum fee per gas.
     pub gas_price: Gas,
     /// Gas limit of this transaction.
     pub gas_limit: Gas,
@@ -49,44 +53,232 @@ pub struct ValidTransaction {
     pub input: Vec<u8>,
     /// Nonce of the transaction.
     pub nonce: U256,
+    /// Addresses and storage keys warmed up before execution
+    /// (EIP-2930).
+    pub access_list: Vec<AccessListItem>,
+    /// Maximum priority fee per gas of a dynamic fee transaction
+    /// (EIP-1559). None for other transactions.
+    pub max_priority_fee_per_gas: Option<Gas>,
+    /// Maximum fee per blob gas of a blob transaction (EIP-4844).
+    /// None for other transactions.
+    pub max_fee_per_blob_gas: Option<Gas>,
+    /// Versioned hashes of the blobs of the transaction (EIP-4844).
+    pub blob_versioned_hashes: Vec<H256>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Intrinsic gas of a transaction, split by where it comes from.
+pub struct IntrinsicGas {
+    /// Gas paid by every transaction.
+    pub base: Gas,
+    /// Gas paid additionally by a contract creation transaction,
+    /// including the cost of its init code words.
+    pub create: Gas,
+    /// Gas paid for the transaction data.
+    pub data: Gas,
+    /// Gas paid for the access list.
+    pub access_list: Gas,
+}
+
+impl IntrinsicGas {
+    /// Total intrinsic gas.
+    pub fn total(&self) -> Gas {
+        self.base + self.create + self.data + self.access_list
+    }
 }
 
 impl ValidTransaction {
     /// Create a valid transaction from a block transaction. Caller is
-    /// always Some.
+    /// always Some. Replay-protected signatures are checked against
+    /// the chain ID of the patch. The gas price is not checked against
+    /// any base fee, use `from_transaction_in_block` for that.
     pub fn from_transaction<P: Patch>(
         transaction: &Transaction, account_state: &AccountState
     ) -> Result<Result<ValidTransaction, PreExecutionError>, RequireError> {
-        let caller = match transaction.caller() {
-            Ok(val) => val,
-            Err(_) => return Ok(Err(PreExecutionError::InvalidCaller)),
+        match Self::from_legacy_transaction::<P>(transaction) {
+            Ok(valid) => valid.validate::<P>(Gas::zero(), Gas::zero(), account_state),
+            Err(err) => Ok(Err(err)),
+        }
+    }
+
+    /// Create a valid transaction from a block transaction, to be
+    /// included in the given block. Caller is always Some. The gas
+    /// price must cover the base fee of the block (EIP-1559).
+    pub fn from_transaction_in_block<P: Patch>(
+        transaction: &Transaction, block: &HeaderParams, account_state: &AccountState
+    ) -> Result<Result<ValidTransaction, PreExecutionError>, RequireError> {
+        match Self::from_legacy_transaction::<P>(transaction) {
+            Ok(valid) => valid.validate::<P>(block.base_fee, block.blob_base_fee(), account_state),
+            Err(err) => Ok(Err(err)),
+        }
+    }
+
+    /// Create a valid transaction from a typed transaction (EIP-2718)
+    /// of any type supported by the patch, to be included in the
+    /// given block. Caller is always Some.
+    pub fn from_typed_transaction<P: Patch>(
+        transaction: &TypedTransaction, block: &HeaderParams, account_state: &AccountState
+    ) -> Result<Result<ValidTransaction, PreExecutionError>, RequireError> {
+        let (chain_id, valid) = match *transaction {
+            TypedTransaction::Legacy(ref transaction) => {
+                return Self::from_transaction_in_block::<P>(transaction, block, account_state);
+            },
+            TypedTransaction::AccessList(ref transaction) => {
+                if !P::has_access_list_transaction() {
+                    return Ok(Err(PreExecutionError::UnsupportedTransactionType));
+                }
+
+                (transaction.chain_id, ValidTransaction {
+                    caller: transaction.caller().ok(),
+                    gas_price: transaction.gas_price,
+                    gas_limit: transaction.gas_limit,
+                    action: transaction.action,
+                    value: transaction.value,
+                    input: transaction.input.clone(),
+                    nonce: transaction.nonce,
+                    access_list: transaction.access_list.clone(),
+                    max_priority_fee_per_gas: None,
+                    max_fee_per_blob_gas: None,
+                    blob_versioned_hashes: Vec::new(),
+                })
+            },
+            TypedTransaction::DynamicFee(ref transaction) => {
+                if !P::has_base_fee() {
+                    return Ok(Err(PreExecutionError::UnsupportedTransactionType));
+                }
+
+                (transaction.chain_id, ValidTransaction {
+                    caller: transaction.caller().ok(),
+                    gas_price: transaction.max_fee_per_gas,
+                    gas_limit: transaction.gas_limit,
+                    action: transaction.action,
+                    value: transaction.value,
+                    input: transaction.input.clone(),
+                    nonce: transaction.nonce,
+                    access_list: transaction.access_list.clone(),
+                    max_priority_fee_per_gas: Some(transaction.max_priority_fee_per_gas),
+                    max_fee_per_blob_gas: None,
+                    blob_versioned_hashes: Vec::new(),
+                })
+            },
+            TypedTransaction::Blob(ref transaction) => {
+                if !P::has_blob_transaction() {
+                    return Ok(Err(PreExecutionError::UnsupportedTransactionType));
+                }
+
+                (transaction.chain_id, ValidTransaction {
+                    caller: transaction.caller().ok(),
+                    gas_price: transaction.max_fee_per_gas,
+                    gas_limit: transaction.gas_limit,
+                    action: TransactionAction::Call(transaction.address),
+                    value: transaction.value,
+                    input: transaction.input.clone(),
+                    nonce: transaction.nonce,
+                    access_list: transaction.access_list.clone(),
+                    max_priority_fee_per_gas: Some(transaction.max_priority_fee_per_gas),
+                    max_fee_per_blob_gas: Some(transaction.max_fee_per_blob_gas),
+                    blob_versioned_hashes: transaction.blob_versioned_hashes.clone(),
+                })
+            },
         };
 
-        let nonce = account_state.nonce(caller)?;
-        if nonce != transaction.nonce {
-            return Ok(Err(PreExecutionError::InvalidNonce));
+        if Some(chain_id) != P::chain_id() {
+            return Ok(Err(PreExecutionError::InvalidChainId));
+        }
+        if valid.caller.is_none() {
+            return Ok(Err(PreExecutionError::InvalidCaller));
+        }
+
+        valid.validate::<P>(block.base_fee, block.blob_base_fee(), account_state)
+    }
+
+    /// Check the signature of a legacy transaction and convert it
+    /// into a not yet validated transaction.
+    fn from_legacy_transaction<P: Patch>(
+        transaction: &Transaction
+    ) -> Result<ValidTransaction, PreExecutionError> {
+        // Signatures without replay protection are accepted by every
+        // patch.
+        let chain_id = transaction.signature.chain_id();
+        if chain_id.is_some() && chain_id != P::chain_id() {
+            return Err(PreExecutionError::InvalidChainId);
         }
 
-        let valid = ValidTransaction {
+        let caller = match transaction.caller() {
+            Ok(val) => val,
+            Err(_) => return Err(PreExecutionError::InvalidCaller),
+        };
+
+        Ok(ValidTransaction {
             caller: Some(caller),
             gas_price: transaction.gas_price,
             gas_limit: transaction.gas_limit,
             action: transaction.action.clone(),
             value: transaction.value,
             input: transaction.input.clone(),
-            nonce: nonce,
-        };
+            nonce: transaction.nonce,
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
+        })
+    }
+
+    /// Check the fees, the nonce, the gas limit and the balance of a
+    /// transaction from a caller.
+    fn validate<P: Patch>(
+        self, base_fee: Gas, blob_base_fee: Gas, account_state: &AccountState
+    ) -> Result<Result<ValidTransaction, PreExecutionError>, RequireError> {
+        let caller = self.caller.unwrap();
+
+        if P::has_base_fee() && self.gas_price < base_fee {
+            return Ok(Err(PreExecutionError::FeeCapBelowBaseFee));
+        }
+        if let Some(max_priority_fee_per_gas) = self.max_priority_fee_per_gas {
+            if max_priority_fee_per_gas > self.gas_price {
+                return Ok(Err(PreExecutionError::PriorityFeeAboveFeeCap));
+            }
+        }
+        if let Some(max_fee_per_blob_gas) = self.max_fee_per_blob_gas {
+            if self.blob_versioned_hashes.is_empty() ||
+                self.blob_versioned_hashes.iter().any(|hash| hash[0] != VERSIONED_HASH_VERSION_KZG)
+            {
+                return Ok(Err(PreExecutionError::InvalidBlobVersionedHashes));
+            }
+            if self.blob_gas() > Gas::from(MAX_BLOB_GAS_PER_BLOCK) {
+                return Ok(Err(PreExecutionError::TooManyBlobs));
+            }
+            if max_fee_per_blob_gas < blob_base_fee {
+                return Ok(Err(PreExecutionError::BlobFeeCapBelowBlobBaseFee));
+            }
+        }
+
+        let nonce = account_state.nonce(caller)?;
+        if nonce != self.nonce {
+            return Ok(Err(PreExecutionError::InvalidNonce));
+        }
+
+        if self.action == TransactionAction::Create {
+            match P::max_initcode_size() {
+                Some(limit) if self.input.len() > limit => {
+                    return Ok(Err(PreExecutionError::InitCodeSizeLimit));
+                },
+                _ => (),
+            }
+        }
 
-        if valid.gas_limit < valid.intrinsic_gas::<P>() {
+        if self.gas_limit < self.intrinsic_gas::<P>() {
             return Ok(Err(PreExecutionError::InsufficientGasLimit));
         }
 
         let balance = account_state.balance(caller)?;
-        if balance < valid.preclaimed_value() + valid.value {
+        let max_blob_fee: U256 = (self.blob_gas() *
+                                  self.max_fee_per_blob_gas.unwrap_or(Gas::zero())).into();
+        if balance < self.preclaimed_value() + self.value + max_blob_fee {
             return Ok(Err(PreExecutionError::InsufficientBalance));
         }
 
-        Ok(Ok(valid))
+        Ok(Ok(self))
     }
 }
 
@@ -99,18 +291,36 @@ impl ValidTransaction {
     /// Intrinsic gas to be paid in prior to this transaction
     /// execution.
     pub fn intrinsic_gas<P: Patch>(&self) -> Gas {
-        let mut gas = Gas::from(G_TRANSACTION);
+        self.intrinsic_gas_breakdown::<P>().total()
+    }
+
+    /// Intrinsic gas of this transaction, split by where it comes
+    /// from.
+    pub fn intrinsic_gas_breakdown<P: Patch>(&self) -> IntrinsicGas {
+        let mut breakdown = IntrinsicGas {
+            base: P::gas_transaction(),
+            create: Gas::zero(),
+            data: Gas::zero(),
+            access_list: Gas::zero(),
+        };
         if self.action == TransactionAction::Create {
-            gas = gas + Gas::from(P::gas_transaction_create());
+            let wordd = Gas::from(self.input.len()) / Gas::from(32u64);
+            let wordr = Gas::from(self.input.len()) % Gas::from(32u64);
+            breakdown.create = P::gas_transaction_create() + P::gas_initcode_word() *
+                if wordr == Gas::zero() { wordd } else { wordd + Gas::from(1u64) };
         }
         for d in &self.input {
             if *d == 0 {
-                gas = gas + Gas::from(G_TXDATAZERO);
+                breakdown.data = breakdown.data + P::gas_transaction_zero_data();
             } else {
-                gas = gas + Gas::from(G_TXDATANONZERO);
+                breakdown.data = breakdown.data + P::gas_transaction_non_zero_data();
             }
         }
-        return gas;
+        for item in &self.access_list {
+            breakdown.access_list = breakdown.access_list + P::gas_access_list_address() +
+                P::gas_access_list_storage_key() * Gas::from(item.storage_keys.len());
+        }
+        breakdown
     }
 
     /// Convert this transaction into a context. Note that this will
@@ -124,7 +334,9 @@ impl ValidTransaction {
                 if self.caller.is_some() {
                     account_state.require(self.caller.unwrap())?;
                 }
-                account_state.require_code(address)?;
+                // The callee is required in full, not only its code,
+                // so that SELFBALANCE can read its balance directly.
+                account_state.require(address)?;
 
                 if self.caller.is_some() && !is_code {
                     let nonce = self.nonce;
@@ -142,6 +354,8 @@ impl ValidTransaction {
                     origin: origin.unwrap_or(self.caller.unwrap_or(system_address!())),
                     apprent_value: self.value,
                     is_system: self.caller.is_none(),
+                    is_static: false,
+                    blob_hashes: self.blob_versioned_hashes,
                 })
             },
             TransactionAction::Create => {
@@ -162,11 +376,31 @@ impl ValidTransaction {
                     origin: origin.unwrap_or(self.caller.unwrap_or(system_address!())),
                     apprent_value: self.value,
                     is_system: self.caller.is_none(),
+                    is_static: false,
+                    blob_hashes: self.blob_versioned_hashes,
                 })
             },
         }
     }
 
+    /// Gas price actually paid given the base fee of the block. For
+    /// a dynamic fee transaction, this is the base fee plus the
+    /// priority fee, capped by the maximum fee per gas.
+    pub fn effective_gas_price(&self, base_fee: Gas) -> Gas {
+        match self.max_priority_fee_per_gas {
+            Some(max_priority_fee_per_gas) => {
+                min(self.gas_price, base_fee + max_priority_fee_per_gas)
+            },
+            None => self.gas_price,
+        }
+    }
+
+    /// Blob gas used by the transaction (EIP-4844). It is paid at the
+    /// blob base fee of the block, separately from the gas.
+    pub fn blob_gas(&self) -> Gas {
+        Gas::from(GAS_PER_BLOB) * Gas::from(self.blob_versioned_hashes.len())
+    }
+
     /// When the execution of a transaction begins, this preclaimed
     /// value is deducted from the account.
     pub fn preclaimed_value(&self) -> U256 {
@@ -186,6 +420,7 @@ enum TransactionVMState<M, P: Patch> {
     Constructing {
         transaction: ValidTransaction,
         block: HeaderParams,
+        is_static: bool,
 
         account_state: AccountState,
         blockhash_state: BlockhashState,
@@ -202,6 +437,21 @@ impl<M: Memory + Default, P: Patch> TransactionVM<M, P> {
         TransactionVM(TransactionVMState::Constructing {
             transaction: transaction,
             block: block,
+            is_static: false,
+
+            account_state: AccountState::default(),
+            blockhash_state: BlockhashState::default(),
+        })
+    }
+
+    /// Create a new VM in static mode, in which any state
+    /// modification fails as in a STATICCALL. This is usually used
+    /// for view calls.
+    pub fn new_static(transaction: ValidTransaction, block: HeaderParams) -> Self {
+        TransactionVM(TransactionVMState::Constructing {
+            transaction: transaction,
+            block: block,
+            is_static: true,
 
             account_state: AccountState::default(),
             blockhash_state: BlockhashState::default(),
@@ -216,6 +466,7 @@ impl<M: Memory + Default, P: Patch> TransactionVM<M, P> {
         TransactionVM(TransactionVMState::Constructing {
             transaction: transaction,
             block: block,
+            is_static: false,
 
             account_state: match vm.0 {
                 TransactionVMState::Constructing { ref account_state, .. } =>
@@ -240,11 +491,30 @@ impl<M: Memory + Default, P: Patch> TransactionVM<M, P> {
                 match vm.machines[0].status() {
                     MachineStatus::ExitedErr(_) =>
                         vm.machines[0].state().context.gas_limit + intrinsic_gas,
+                    MachineStatus::ExitedRevert =>
+                        vm.machines[0].state().total_used_gas() + intrinsic_gas,
+                    MachineStatus::ExitedOk => {
+                        let total_used = vm.machines[0].state().memory_gas() + vm.machines[0].state().used_gas + intrinsic_gas;
+                        total_used - self.applied_refund()
+                    }
+                    _ => Gas::zero(),
+                }
+            }
+            TransactionVMState::Constructing { .. } => Gas::zero(),
+        }
+    }
+
+    /// Returns the refund actually applied to the used gas, that is,
+    /// the accumulated refunded gas capped by the patch's refund
+    /// quotient. Zero unless the transaction exited successfully.
+    pub fn applied_refund(&self) -> Gas {
+        match self.0 {
+            TransactionVMState::Running { ref vm, intrinsic_gas, .. } => {
+                match vm.machines[0].status() {
                     MachineStatus::ExitedOk => {
                         let total_used = vm.machines[0].state().memory_gas() + vm.machines[0].state().used_gas + intrinsic_gas;
-                        let refund_cap = total_used / Gas::from(2u64);
-                        let refunded = min(refund_cap, vm.machines[0].state().refunded_gas);
-                        total_used - refunded
+                        let refund_cap = total_used / Gas::from(P::max_refund_quotient());
+                        min(refund_cap, vm.machines[0].state().refunded_gas)
                     }
                     _ => Gas::zero(),
                 }
@@ -299,7 +569,7 @@ impl<M: Memory + Default, P: Patch> VM for TransactionVM<M, P> {
 
     fn step(&mut self) -> Result<(), RequireError> {
         let cgas: Gas;
-        let ccontext: Context;
+        let mut ccontext: Context;
         let cblock: HeaderParams;
         let caccount_state: AccountState;
         let cblockhash_state: BlockhashState;
@@ -343,19 +613,47 @@ impl<M: Memory + Default, P: Patch> VM for TransactionVM<M, P> {
                 }
             }
             TransactionVMState::Constructing {
-                ref transaction, ref block,
+                ref transaction, ref block, is_static,
                 ref mut account_state, ref blockhash_state } => {
 
+                let mut transaction = transaction.clone();
+                if P::has_base_fee() {
+                    transaction.gas_price = transaction.effective_gas_price(block.base_fee);
+                }
+                transaction.max_priority_fee_per_gas = None;
+
                 let address = transaction.address();
                 account_state.require(address)?;
+                if !P::empty_considered_exists() {
+                    account_state.require(block.beneficiary)?;
+                }
 
                 ccode_deposit = match transaction.action {
                     TransactionAction::Call(_) => false,
                     TransactionAction::Create => true,
                 };
                 cgas = transaction.intrinsic_gas::<P>();
+                let blob_fee: U256 = (transaction.blob_gas() * block.blob_base_fee()).into();
+                let blob_caller = transaction.caller;
                 cpreclaimed_value = transaction.preclaimed_value();
-                ccontext = transaction.clone().into_context(cgas, None, account_state, false)?;
+                account_state.reset_original_storage();
+                account_state.clear_accessed();
+                if P::has_access_tracking() {
+                    for item in &transaction.access_list {
+                        account_state.access_address(item.address);
+                        for key in &item.storage_keys {
+                            account_state.access_storage(item.address, U256::from(*key));
+                        }
+                    }
+                }
+                ccontext = transaction.into_context(cgas, None, account_state, false)?;
+                if P::has_blob_transaction() {
+                    if let Some(caller) = blob_caller {
+                        // The blob fee is burnt and never refunded.
+                        account_state.decrease_balance(caller, blob_fee);
+                    }
+                }
+                ccontext.is_static = is_static;
                 cblock = block.clone();
                 caccount_state = account_state.clone();
                 cblockhash_state = blockhash_state.clone();
@@ -436,11 +734,103 @@ impl<M: Memory + Default, P: Patch> VM for TransactionVM<M, P> {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use ::*;
     use bigint::*;
-    use block::TransactionAction;
+    use block::{TransactionAction, UnsignedTransaction, ClassicSignaturePatch,
+                GlobalSignaturePatch};
+    use errors::{PreExecutionError, RequireError, OnChainError};
+    use secp256k1::SECP256K1;
+    use secp256k1::key::SecretKey;
     use std::str::FromStr;
+    use std::collections::HashMap;
+    use hexutil::read_hex;
+
+    /// Address the code of a test is deployed at.
+    pub fn target() -> Address {
+        Address::from_str("0x1000000000000000000000000000000000000000").unwrap()
+    }
+
+    /// A system transaction calling `target()`.
+    pub fn transaction() -> ValidTransaction {
+        ValidTransaction {
+            caller: None,
+            gas_price: Gas::zero(),
+            gas_limit: Gas::from(100000u64),
+            action: TransactionAction::Call(target()),
+            value: U256::zero(),
+            input: Vec::new(),
+            nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
+        }
+    }
+
+    /// The genesis block, with no base fee.
+    pub fn block() -> HeaderParams {
+        HeaderParams {
+            beneficiary: Address::default(),
+            timestamp: 0,
+            number: U256::zero(),
+            difficulty: U256::zero(),
+            gas_limit: Gas::zero(),
+            base_fee: Gas::zero(),
+            excess_blob_gas: Gas::zero(),
+        }
+    }
+
+    /// Execute a transaction with the given accounts. Any other
+    /// account required does not exist, and any other storage is
+    /// zero.
+    pub fn execute<P: Patch>(
+        transaction: ValidTransaction, block: HeaderParams, accounts: Vec<AccountCommitment>
+    ) -> SeqTransactionVM<P> {
+        let mut vm = SeqTransactionVM::<P>::new(transaction, block);
+        for account in accounts {
+            vm.commit_account(account).unwrap();
+        }
+        loop {
+            match vm.fire() {
+                Ok(()) => return vm,
+                Err(RequireError::Account(address)) |
+                Err(RequireError::AccountCode(address)) => {
+                    vm.commit_account(AccountCommitment::Nonexist(address)).unwrap();
+                },
+                Err(RequireError::AccountStorage(address, index)) => {
+                    vm.commit_account(AccountCommitment::Storage {
+                        address: address,
+                        index: index,
+                        value: M256::zero(),
+                    }).unwrap();
+                },
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+
+    /// Call `code` deployed at `target()` with `input` in a system
+    /// transaction, and check that it exits successfully.
+    pub fn run<P: Patch>(
+        code: &str, input: &[u8], mut accounts: Vec<AccountCommitment>
+    ) -> SeqTransactionVM<P> {
+        accounts.insert(0, AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            code: read_hex(code).unwrap(),
+        });
+        let vm = execute::<P>(ValidTransaction {
+            input: input.to_vec(),
+            ..transaction()
+        }, block(), accounts);
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        vm
+    }
 
     #[test]
     fn system_transaction() {
@@ -452,6 +842,10 @@ mod tests {
             value: U256::from_str("0xffffffffffffffff").unwrap(),
             input: Vec::new(),
             nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
         };
         let mut vm = SeqTransactionVM::<EIP160Patch>::new(transaction, HeaderParams {
             beneficiary: Address::default(),
@@ -459,6 +853,8 @@ mod tests {
             number: U256::zero(),
             difficulty: U256::zero(),
             gas_limit: Gas::zero(),
+            base_fee: Gas::zero(),
+            excess_blob_gas: Gas::zero(),
         });
         vm.commit_account(AccountCommitment::Nonexist(Address::default())).unwrap();
         vm.fire().unwrap();
@@ -479,4 +875,401 @@ mod tests {
             _ => panic!()
         }
     }
+
+    #[test]
+    fn touched_empty_account_cleared() {
+        let vm = run::<SpuriousDragonPatch>("0x", &[], Vec::new());
+        assert_eq!(vm.removed(), &[target()]);
+    }
+
+    #[test]
+    fn code_size_limit() {
+        let vm = execute::<SpuriousDragonPatch>(ValidTransaction {
+            gas_limit: Gas::from(200000u64),
+            action: TransactionAction::Create,
+            // PUSH2 0x6001 PUSH1 0x00 RETURN
+            input: vec![0x61, 0x60, 0x01, 0x60, 0x00, 0xf3],
+            ..transaction()
+        }, block(), Vec::new());
+
+        match vm.status() {
+            VMStatus::ExitedErr(OnChainError::CodeSizeLimit) => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(vm.real_used_gas(), Gas::from(200000u64));
+    }
+
+    #[test]
+    fn revert() {
+        let vm = execute::<ByzantiumPatch>(ValidTransaction {
+            gas_limit: Gas::from(200000u64),
+            action: TransactionAction::Create,
+            // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 REVERT
+            input: vec![0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xfd],
+            ..transaction()
+        }, block(), Vec::new());
+
+        match vm.status() {
+            VMStatus::ExitedRevert => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(vm.out()[31], 0x2a);
+        assert!(vm.real_used_gas() < Gas::from(200000u64));
+    }
+
+    #[test]
+    fn return_data() {
+        let vm = execute::<ByzantiumPatch>(ValidTransaction {
+            gas_limit: Gas::from(200000u64),
+            action: TransactionAction::Create,
+            // PUSH10 <code of the revert test> PUSH1 0x00 MSTORE
+            // PUSH1 0x0a PUSH1 0x16 PUSH1 0x00 CREATE POP
+            // RETURNDATASIZE PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+            input: vec![0x69, 0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xfd,
+                        0x60, 0x00, 0x52, 0x60, 0x0a, 0x60, 0x16, 0x60, 0x00, 0xf0, 0x50,
+                        0x3d, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3],
+            ..transaction()
+        }, block(), Vec::new());
+
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(vm.out()[31], 0x20);
+    }
+
+    #[test]
+    fn static_transaction() {
+        let mut vm = SeqTransactionVM::<ByzantiumPatch>::new_static(transaction(), block());
+        vm.commit_account(AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // PUSH1 0x01 PUSH1 0x00 SSTORE
+            code: vec![0x60, 0x01, 0x60, 0x00, 0x55],
+        }).unwrap();
+        vm.commit_account(AccountCommitment::Nonexist(Address::default())).unwrap();
+        vm.fire().unwrap();
+
+        match vm.status() {
+            VMStatus::ExitedErr(OnChainError::StaticStateChange) => (),
+            status => panic!("{:?}", status),
+        }
+    }
+
+    #[test]
+    fn intrinsic_gas_breakdown() {
+        let transaction = ValidTransaction {
+            caller: Some(Address::default()),
+            action: TransactionAction::Create,
+            input: vec![0, 1, 2],
+            ..transaction()
+        };
+
+        let breakdown = transaction.intrinsic_gas_breakdown::<ByzantiumPatch>();
+        assert_eq!(breakdown, IntrinsicGas {
+            base: Gas::from(21000u64),
+            create: Gas::from(32000u64),
+            data: Gas::from(140u64),
+            access_list: Gas::zero(),
+        });
+        assert_eq!(transaction.intrinsic_gas::<ByzantiumPatch>(), Gas::from(53140u64));
+
+        let breakdown = transaction.intrinsic_gas_breakdown::<IstanbulPatch>();
+        assert_eq!(breakdown.data, Gas::from(36u64));
+        assert_eq!(transaction.intrinsic_gas::<IstanbulPatch>(), Gas::from(53036u64));
+
+        let breakdown = transaction.intrinsic_gas_breakdown::<ShanghaiPatch>();
+        assert_eq!(breakdown.create, Gas::from(32002u64));
+    }
+
+    #[test]
+    fn initcode_size_limit() {
+        let mut account_state = AccountState::default();
+        account_state.commit(AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: Address::default(),
+            balance: U256::zero(),
+            code: Vec::new(),
+        }).unwrap();
+        let create = ValidTransaction {
+            caller: Some(Address::default()),
+            gas_limit: Gas::from(1000000u64),
+            action: TransactionAction::Create,
+            input: vec![0; 0xc001],
+            ..transaction()
+        };
+        assert!(create.clone().validate::<LondonPatch>(Gas::zero(), Gas::zero(), &account_state)
+                .unwrap().is_ok());
+        match create.validate::<ShanghaiPatch>(Gas::zero(), Gas::zero(), &account_state).unwrap() {
+            Err(PreExecutionError::InitCodeSizeLimit) => (),
+            result => panic!("{:?}", result),
+        }
+
+        let vm = execute::<ShanghaiPatch>(ValidTransaction {
+            gas_limit: Gas::from(1000000u64),
+            ..transaction()
+        }, block(), vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // PUSH2 0xc001 PUSH1 0x00 PUSH1 0x00 CREATE
+            code: read_hex("0x61c00160006000f0").unwrap(),
+        }]);
+        match vm.status() {
+            VMStatus::ExitedErr(OnChainError::InitCodeSizeLimit) => (),
+            status => panic!("{:?}", status),
+        }
+    }
+
+    #[test]
+    fn access_list() {
+        let other = Address::from_str("0x00000000000000000000000000000000000000ff").unwrap();
+        let transaction = ValidTransaction {
+            access_list: vec![AccessListItem {
+                address: other,
+                storage_keys: Vec::new(),
+            }, AccessListItem {
+                address: target(),
+                storage_keys: vec![H256::default()],
+            }],
+            ..transaction()
+        };
+        assert_eq!(transaction.intrinsic_gas_breakdown::<BerlinPatch>().access_list,
+                   Gas::from(2400u64 * 2 + 1900));
+
+        let vm = execute::<BerlinPatch>(transaction, block(), vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // PUSH1 0xff BALANCE PUSH1 0x00 SLOAD STOP
+            code: read_hex("0x60ff3160005400").unwrap(),
+        }]);
+
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        // Both accesses are warm because of the access list.
+        assert_eq!(vm.real_used_gas(), Gas::from(21000u64 + 2400 * 2 + 1900 + 3 + 100 + 3 + 100));
+    }
+
+    #[test]
+    fn base_fee() {
+        let caller = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let beneficiary = Address::from_str("0x0000000000000000000000000000000000000002").unwrap();
+        let transaction = ValidTransaction {
+            caller: Some(caller),
+            gas_price: Gas::from(10u64),
+            max_priority_fee_per_gas: Some(Gas::from(2u64)),
+            ..transaction()
+        };
+        assert_eq!(transaction.effective_gas_price(Gas::from(5u64)), Gas::from(7u64));
+        assert_eq!(transaction.effective_gas_price(Gas::from(9u64)), Gas::from(10u64));
+
+        let legacy = ValidTransaction {
+            gas_price: Gas::from(4u64),
+            max_priority_fee_per_gas: None,
+            ..transaction.clone()
+        };
+        match legacy.validate::<LondonPatch>(Gas::from(5u64), Gas::zero(), &AccountState::default()) {
+            Ok(Err(PreExecutionError::FeeCapBelowBaseFee)) => (),
+            _ => panic!(),
+        }
+
+        let vm = execute::<LondonPatch>(transaction, HeaderParams {
+            beneficiary: beneficiary,
+            base_fee: Gas::from(5u64),
+            ..block()
+        }, vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: caller,
+            balance: U256::from(1000000u64),
+            code: Vec::new(),
+        }, AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: beneficiary,
+            balance: U256::zero(),
+            code: Vec::new(),
+        }, AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // BASEFEE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+            code: read_hex("0x4860005260206000f3").unwrap(),
+        }]);
+
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        assert_eq!(U256::from(vm.out()), U256::from(5u64));
+        assert_eq!(vm.real_used_gas(), Gas::from(21017u64));
+
+        let mut balances = HashMap::new();
+        for account in vm.accounts() {
+            if let &AccountChange::Full { address, balance, .. } = account {
+                balances.insert(address, balance);
+            }
+        }
+        // The caller pays the base fee plus the priority fee, but
+        // the beneficiary only receives the priority fee.
+        assert_eq!(balances[&caller], U256::from(1000000u64 - 21017 * 7));
+        assert_eq!(balances[&beneficiary], U256::from(21017u64 * 2));
+    }
+
+    fn clear_storage<P: Patch>() -> SeqTransactionVM<P> {
+        // PUSH1 0 PUSH1 0 SSTORE STOP
+        run::<P>("0x600060005500", &[], vec![AccountCommitment::Storage {
+            address: target(),
+            index: U256::zero(),
+            value: M256::from(1u64),
+        }])
+    }
+
+    #[test]
+    fn refund_quotient() {
+        // Used gas before refund is 21000 + 3 + 3 + 2100 + 2900.
+        let vm = clear_storage::<BerlinPatch>();
+        assert_eq!(vm.refunded_gas(), Gas::from(15000u64));
+        assert_eq!(vm.applied_refund(), Gas::from(26006u64 / 2));
+        assert_eq!(vm.real_used_gas(), Gas::from(26006u64 - 26006 / 2));
+
+        let vm = clear_storage::<LondonPatch>();
+        assert_eq!(vm.refunded_gas(), Gas::from(4800u64));
+        assert_eq!(vm.applied_refund(), Gas::from(4800u64));
+        assert_eq!(vm.real_used_gas(), Gas::from(26006u64 - 4800));
+    }
+
+    #[test]
+    fn blob_transaction() {
+        let caller = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let mut blob_hash = H256::from(1u64);
+        blob_hash[0] = 0x01;
+        let transaction = ValidTransaction {
+            caller: Some(caller),
+            max_priority_fee_per_gas: Some(Gas::zero()),
+            max_fee_per_blob_gas: Some(Gas::from(2u64)),
+            blob_versioned_hashes: vec![H256::from(1u64)],
+            ..transaction()
+        };
+        let mut account_state = AccountState::default();
+        account_state.commit(AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: caller,
+            balance: U256::from(1000000u64),
+            code: Vec::new(),
+        }).unwrap();
+        match transaction.clone().validate::<CancunPatch>(Gas::zero(), Gas::from(3u64), &account_state).unwrap() {
+            Err(PreExecutionError::InvalidBlobVersionedHashes) => (),
+            result => panic!("{:?}", result),
+        }
+        let too_many_blobs = ValidTransaction {
+            blob_versioned_hashes: vec![blob_hash; 7],
+            ..transaction.clone()
+        };
+        match too_many_blobs.validate::<CancunPatch>(Gas::zero(), Gas::from(2u64), &account_state).unwrap() {
+            Err(PreExecutionError::TooManyBlobs) => (),
+            result => panic!("{:?}", result),
+        }
+        let transaction = ValidTransaction {
+            blob_versioned_hashes: vec![blob_hash],
+            ..transaction
+        };
+        match transaction.clone().validate::<CancunPatch>(Gas::zero(), Gas::from(3u64), &account_state).unwrap() {
+            Err(PreExecutionError::BlobFeeCapBelowBlobBaseFee) => (),
+            result => panic!("{:?}", result),
+        }
+        let transaction = transaction.validate::<CancunPatch>(Gas::zero(), Gas::from(2u64), &account_state)
+            .unwrap().unwrap();
+        assert_eq!(transaction.blob_gas(), Gas::from(131072u64));
+
+        let block = HeaderParams { excess_blob_gas: Gas::from(2314057u64), ..block() };
+        assert_eq!(block.blob_base_fee(), Gas::from(1u64));
+        let block = HeaderParams { excess_blob_gas: Gas::from(33384770u64), ..block };
+        assert_eq!(block.blob_base_fee(), Gas::from(22026u64));
+        // Smallest excess blob gas with a blob base fee of 2.
+        let block = HeaderParams { excess_blob_gas: Gas::from(2314058u64), ..block };
+        assert_eq!(block.blob_base_fee(), Gas::from(2u64));
+
+        let vm = execute::<CancunPatch>(transaction, block, vec![AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: caller,
+            balance: U256::from(1000000u64),
+            code: Vec::new(),
+        }, AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: target(),
+            balance: U256::zero(),
+            // PUSH0 BLOBHASH PUSH0 MSTORE BLOBBASEFEE PUSH1 32 MSTORE
+            // PUSH1 64 PUSH0 RETURN
+            code: read_hex("0x5f495f524a60205260405ff3").unwrap(),
+        }]);
+
+        match vm.status() {
+            VMStatus::ExitedOk => (),
+            status => panic!("{:?}", status),
+        }
+        let mut expected = [0u8; 64];
+        expected[0..32].copy_from_slice(&blob_hash);
+        expected[63] = 2;
+        assert_eq!(vm.out(), &expected[..]);
+
+        let mut balances = HashMap::new();
+        for account in vm.accounts() {
+            if let &AccountChange::Full { address, balance, .. } = account {
+                balances.insert(address, balance);
+            }
+        }
+        // The blob fee is burnt on top of the (zero) gas fee.
+        assert_eq!(balances[&caller], U256::from(1000000u64 - 131072 * 2));
+    }
+
+    #[test]
+    fn eip155_chain_id() {
+        let secret_key = SecretKey::from_slice(&SECP256K1, &[1u8; 32]).unwrap();
+        let unsigned = || UnsignedTransaction {
+            nonce: U256::zero(),
+            gas_price: Gas::zero(),
+            gas_limit: Gas::from(21000u64),
+            action: TransactionAction::Call(Address::default()),
+            value: U256::zero(),
+            input: Vec::new(),
+        };
+        let caller = Address::from_str("0x1a642f0e3c3af545e7acbd38b07251b3990914f1").unwrap();
+        let mut account_state = AccountState::default();
+        account_state.commit(AccountCommitment::Full {
+            nonce: U256::zero(),
+            address: caller,
+            balance: U256::zero(),
+            code: Vec::new(),
+        }).unwrap();
+
+        let classic = unsigned().sign::<ClassicSignaturePatch>(&secret_key);
+        let foundation = unsigned().sign::<FoundationSignaturePatch>(&secret_key);
+        let legacy = unsigned().sign::<GlobalSignaturePatch>(&secret_key);
+
+        match ValidTransaction::from_transaction::<EIP160Patch>(&classic, &account_state).unwrap() {
+            Ok(valid) => assert_eq!(valid.caller, Some(caller)),
+            Err(err) => panic!("{:?}", err),
+        }
+        match ValidTransaction::from_transaction::<ByzantiumPatch<FoundationSignaturePatch>>(
+            &foundation, &account_state).unwrap() {
+            Ok(valid) => assert_eq!(valid.caller, Some(caller)),
+            Err(err) => panic!("{:?}", err),
+        }
+        match ValidTransaction::from_transaction::<ByzantiumPatch>(&legacy, &account_state).unwrap() {
+            Ok(valid) => assert_eq!(valid.caller, Some(caller)),
+            Err(err) => panic!("{:?}", err),
+        }
+
+        match ValidTransaction::from_transaction::<EIP160Patch>(&foundation, &account_state).unwrap() {
+            Err(PreExecutionError::InvalidChainId) => (),
+            _ => panic!(),
+        }
+        match ValidTransaction::from_transaction::<HomesteadPatch>(&classic, &account_state).unwrap() {
+            Err(PreExecutionError::InvalidChainId) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -0,0 +1,646 @@
+//! Typed transaction envelopes (EIP-2718).
+
+use rlp::{self, Encodable, Decodable, RlpStream, DecoderError, UntrustedRlp};
+use bigint::{Address, Gas, H256, U256};
+use block::{Transaction, TransactionAction};
+use secp256k1::{SECP256K1, RecoverableSignature, RecoveryId, Message, Error};
+use sha3::{Digest, Keccak256};
+use std::str::FromStr;
+
+/// Type byte of an access list transaction (EIP-2930).
+pub const ACCESS_LIST_TRANSACTION_TYPE: u8 = 0x01;
+/// Type byte of a dynamic fee transaction (EIP-1559).
+pub const DYNAMIC_FEE_TRANSACTION_TYPE: u8 = 0x02;
+/// Type byte of a blob transaction (EIP-4844).
+pub const BLOB_TRANSACTION_TYPE: u8 = 0x03;
+/// Version byte of a blob versioned hash derived from a KZG
+/// commitment (EIP-4844).
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+fn signing_hash(transaction_type: u8, stream: RlpStream) -> H256 {
+    let mut hasher = Keccak256::default();
+    hasher.input(&[transaction_type]);
+    hasher.input(&stream.drain());
+    H256::from(hasher.result().as_slice())
+}
+
+fn recover(signature: &TypedTransactionSignature, hash: H256) -> Result<Address, Error> {
+    if !signature.is_valid() {
+        return Err(Error::InvalidSignature);
+    }
+
+    let mut sig = [0u8; 64];
+    signature.r.to_big_endian(&mut sig[0..32]);
+    signature.s.to_big_endian(&mut sig[32..64]);
+    let recid = RecoveryId::from_i32(signature.odd_y_parity as i32)?;
+    let sig = RecoverableSignature::from_compact(&SECP256K1, &sig, recid)?;
+
+    let message = Message::from_slice(&hash)?;
+    let public_key = SECP256K1.recover(&message, &sig)?;
+    let key = public_key.serialize_vec(&SECP256K1, false);
+    Ok(Address::from(&Keccak256::digest(&key[1..65])[12..]))
+}
+
+fn decode_y_parity(rlp: &UntrustedRlp, index: usize) -> Result<bool, DecoderError> {
+    match rlp.val_at::<u8>(index)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(DecoderError::Custom("invalid y parity")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An address and its storage keys accessed by a transaction.
+pub struct AccessListItem {
+    /// Accessed address.
+    pub address: Address,
+    /// Accessed storage keys of the address.
+    pub storage_keys: Vec<H256>,
+}
+
+impl Encodable for AccessListItem {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(2);
+        s.append(&self.address);
+        s.append_list(&self.storage_keys);
+    }
+}
+
+impl Decodable for AccessListItem {
+    fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 2 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            address: rlp.val_at(0)?,
+            storage_keys: rlp.list_at(1)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Signature of a typed transaction. The recovery id is stored
+/// directly instead of being folded into `v`.
+pub struct TypedTransactionSignature {
+    /// Parity of the y coordinate of the signature point.
+    pub odd_y_parity: bool,
+    /// R value of the signature.
+    pub r: U256,
+    /// S value of the signature.
+    pub s: U256,
+}
+
+impl TypedTransactionSignature {
+    /// Whether `r` and `s` are non-zero and `s` is in the lower half
+    /// of the curve order (EIP-2).
+    pub fn is_valid(&self) -> bool {
+        !self.r.is_zero() && !self.s.is_zero() &&
+            self.s <= U256::from_str("0x7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0").unwrap()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An access list transaction (EIP-2930).
+pub struct AccessListTransaction {
+    /// Chain ID the transaction is signed for.
+    pub chain_id: u64,
+    /// Nonce of the transaction.
+    pub nonce: U256,
+    /// Gas price of the transaction.
+    pub gas_price: Gas,
+    /// Gas limit of the transaction.
+    pub gas_limit: Gas,
+    /// Action of the transaction.
+    pub action: TransactionAction,
+    /// Value of the transaction.
+    pub value: U256,
+    /// Data or init associated with the transaction.
+    pub input: Vec<u8>,
+    /// Addresses and storage keys warmed up before execution.
+    pub access_list: Vec<AccessListItem>,
+    /// Signature of the transaction.
+    pub signature: TypedTransactionSignature,
+}
+
+impl AccessListTransaction {
+    fn unsigned_rlp_append(&self, s: &mut RlpStream, len: usize) {
+        s.begin_list(len);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.gas_price);
+        s.append(&self.gas_limit);
+        s.append(&self.action);
+        s.append(&self.value);
+        s.append(&self.input);
+        s.append_list(&self.access_list);
+    }
+
+    /// Hash signed by the caller of the transaction.
+    pub fn signing_hash(&self) -> H256 {
+        let mut stream = RlpStream::new();
+        self.unsigned_rlp_append(&mut stream, 8);
+        signing_hash(ACCESS_LIST_TRANSACTION_TYPE, stream)
+    }
+
+    /// Recover the caller of the transaction from its signature.
+    pub fn caller(&self) -> Result<Address, Error> {
+        recover(&self.signature, self.signing_hash())
+    }
+}
+
+impl Encodable for AccessListTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        self.unsigned_rlp_append(s, 11);
+        s.append(&(self.signature.odd_y_parity as u8));
+        s.append(&self.signature.r);
+        s.append(&self.signature.s);
+    }
+}
+
+impl Decodable for AccessListTransaction {
+    fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 11 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            gas_price: rlp.val_at(2)?,
+            gas_limit: rlp.val_at(3)?,
+            action: rlp.val_at(4)?,
+            value: rlp.val_at(5)?,
+            input: rlp.val_at(6)?,
+            access_list: rlp.list_at(7)?,
+            signature: TypedTransactionSignature {
+                odd_y_parity: decode_y_parity(rlp, 8)?,
+                r: rlp.val_at(9)?,
+                s: rlp.val_at(10)?,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A dynamic fee transaction (EIP-1559).
+pub struct DynamicFeeTransaction {
+    /// Chain ID the transaction is signed for.
+    pub chain_id: u64,
+    /// Nonce of the transaction.
+    pub nonce: U256,
+    /// Maximum fee per gas paid to the beneficiary on top of the
+    /// base fee.
+    pub max_priority_fee_per_gas: Gas,
+    /// Maximum total fee per gas, including the base fee.
+    pub max_fee_per_gas: Gas,
+    /// Gas limit of the transaction.
+    pub gas_limit: Gas,
+    /// Action of the transaction.
+    pub action: TransactionAction,
+    /// Value of the transaction.
+    pub value: U256,
+    /// Data or init associated with the transaction.
+    pub input: Vec<u8>,
+    /// Addresses and storage keys warmed up before execution.
+    pub access_list: Vec<AccessListItem>,
+    /// Signature of the transaction.
+    pub signature: TypedTransactionSignature,
+}
+
+impl DynamicFeeTransaction {
+    fn unsigned_rlp_append(&self, s: &mut RlpStream, len: usize) {
+        s.begin_list(len);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.action);
+        s.append(&self.value);
+        s.append(&self.input);
+        s.append_list(&self.access_list);
+    }
+
+    /// Hash signed by the caller of the transaction.
+    pub fn signing_hash(&self) -> H256 {
+        let mut stream = RlpStream::new();
+        self.unsigned_rlp_append(&mut stream, 9);
+        signing_hash(DYNAMIC_FEE_TRANSACTION_TYPE, stream)
+    }
+
+    /// Recover the caller of the transaction from its signature.
+    pub fn caller(&self) -> Result<Address, Error> {
+        recover(&self.signature, self.signing_hash())
+    }
+}
+
+impl Encodable for DynamicFeeTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        self.unsigned_rlp_append(s, 12);
+        s.append(&(self.signature.odd_y_parity as u8));
+        s.append(&self.signature.r);
+        s.append(&self.signature.s);
+    }
+}
+
+impl Decodable for DynamicFeeTransaction {
+    fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 12 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            action: rlp.val_at(5)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at(7)?,
+            access_list: rlp.list_at(8)?,
+            signature: TypedTransactionSignature {
+                odd_y_parity: decode_y_parity(rlp, 9)?,
+                r: rlp.val_at(10)?,
+                s: rlp.val_at(11)?,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A blob transaction (EIP-4844). It always calls an address, and
+/// carries versioned hashes of the blobs it commits to.
+pub struct BlobTransaction {
+    /// Chain ID the transaction is signed for.
+    pub chain_id: u64,
+    /// Nonce of the transaction.
+    pub nonce: U256,
+    /// Maximum fee per gas paid to the beneficiary on top of the
+    /// base fee.
+    pub max_priority_fee_per_gas: Gas,
+    /// Maximum total fee per gas, including the base fee.
+    pub max_fee_per_gas: Gas,
+    /// Gas limit of the transaction.
+    pub gas_limit: Gas,
+    /// Address called by the transaction.
+    pub address: Address,
+    /// Value of the transaction.
+    pub value: U256,
+    /// Data associated with the transaction.
+    pub input: Vec<u8>,
+    /// Addresses and storage keys warmed up before execution.
+    pub access_list: Vec<AccessListItem>,
+    /// Maximum fee per blob gas.
+    pub max_fee_per_blob_gas: Gas,
+    /// Versioned hashes of the blobs of the transaction.
+    pub blob_versioned_hashes: Vec<H256>,
+    /// Signature of the transaction.
+    pub signature: TypedTransactionSignature,
+}
+
+impl BlobTransaction {
+    fn unsigned_rlp_append(&self, s: &mut RlpStream, len: usize) {
+        s.begin_list(len);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.address);
+        s.append(&self.value);
+        s.append(&self.input);
+        s.append_list(&self.access_list);
+        s.append(&self.max_fee_per_blob_gas);
+        s.append_list(&self.blob_versioned_hashes);
+    }
+
+    /// Hash signed by the caller of the transaction.
+    pub fn signing_hash(&self) -> H256 {
+        let mut stream = RlpStream::new();
+        self.unsigned_rlp_append(&mut stream, 11);
+        signing_hash(BLOB_TRANSACTION_TYPE, stream)
+    }
+
+    /// Recover the caller of the transaction from its signature.
+    pub fn caller(&self) -> Result<Address, Error> {
+        recover(&self.signature, self.signing_hash())
+    }
+}
+
+impl Encodable for BlobTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        self.unsigned_rlp_append(s, 14);
+        s.append(&(self.signature.odd_y_parity as u8));
+        s.append(&self.signature.r);
+        s.append(&self.signature.s);
+    }
+}
+
+impl Decodable for BlobTransaction {
+    fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 14 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            address: rlp.val_at(5)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at(7)?,
+            access_list: rlp.list_at(8)?,
+            max_fee_per_blob_gas: rlp.val_at(9)?,
+            blob_versioned_hashes: rlp.list_at(10)?,
+            signature: TypedTransactionSignature {
+                odd_y_parity: decode_y_parity(rlp, 11)?,
+                r: rlp.val_at(12)?,
+                s: rlp.val_at(13)?,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A transaction of any supported type.
+pub enum TypedTransaction {
+    /// Legacy transaction without a type byte.
+    Legacy(Transaction),
+    /// Access list transaction (EIP-2930).
+    AccessList(AccessListTransaction),
+    /// Dynamic fee transaction (EIP-1559).
+    DynamicFee(DynamicFeeTransaction),
+    /// Blob transaction (EIP-4844).
+    Blob(BlobTransaction),
+}
+
+impl TypedTransaction {
+    /// Type byte of the transaction. Legacy transactions are
+    /// considered type zero.
+    pub fn transaction_type(&self) -> u8 {
+        match *self {
+            TypedTransaction::Legacy(_) => 0x00,
+            TypedTransaction::AccessList(_) => ACCESS_LIST_TRANSACTION_TYPE,
+            TypedTransaction::DynamicFee(_) => DYNAMIC_FEE_TRANSACTION_TYPE,
+            TypedTransaction::Blob(_) => BLOB_TRANSACTION_TYPE,
+        }
+    }
+
+    /// Recover the caller of the transaction from its signature.
+    pub fn caller(&self) -> Result<Address, Error> {
+        match *self {
+            TypedTransaction::Legacy(ref transaction) => transaction.caller(),
+            TypedTransaction::AccessList(ref transaction) => transaction.caller(),
+            TypedTransaction::DynamicFee(ref transaction) => transaction.caller(),
+            TypedTransaction::Blob(ref transaction) => transaction.caller(),
+        }
+    }
+
+    /// Encode the transaction as an envelope, which is the type byte
+    /// followed by the payload. Legacy transactions are encoded as
+    /// plain RLP.
+    pub fn envelope(&self) -> Vec<u8> {
+        match *self {
+            TypedTransaction::Legacy(ref transaction) => rlp::encode(transaction).to_vec(),
+            TypedTransaction::AccessList(ref transaction) => {
+                let mut ret = vec![ACCESS_LIST_TRANSACTION_TYPE];
+                ret.extend_from_slice(&rlp::encode(transaction));
+                ret
+            },
+            TypedTransaction::DynamicFee(ref transaction) => {
+                let mut ret = vec![DYNAMIC_FEE_TRANSACTION_TYPE];
+                ret.extend_from_slice(&rlp::encode(transaction));
+                ret
+            },
+            TypedTransaction::Blob(ref transaction) => {
+                let mut ret = vec![BLOB_TRANSACTION_TYPE];
+                ret.extend_from_slice(&rlp::encode(transaction));
+                ret
+            },
+        }
+    }
+
+    /// Decode a transaction from its envelope.
+    pub fn from_envelope(data: &[u8]) -> Result<Self, DecoderError> {
+        match data.first() {
+            None => Err(DecoderError::RlpIsTooShort),
+            Some(&v) if v >= 0xc0 =>
+                Ok(TypedTransaction::Legacy(UntrustedRlp::new(data).as_val()?)),
+            Some(&ACCESS_LIST_TRANSACTION_TYPE) =>
+                Ok(TypedTransaction::AccessList(UntrustedRlp::new(&data[1..]).as_val()?)),
+            Some(&DYNAMIC_FEE_TRANSACTION_TYPE) =>
+                Ok(TypedTransaction::DynamicFee(UntrustedRlp::new(&data[1..]).as_val()?)),
+            Some(&BLOB_TRANSACTION_TYPE) =>
+                Ok(TypedTransaction::Blob(UntrustedRlp::new(&data[1..]).as_val()?)),
+            Some(_) => Err(DecoderError::Custom("unknown transaction type")),
+        }
+    }
+}
+
+/// Encoding of the transaction inside a block body. Use `envelope`
+/// for the raw transaction.
+impl Encodable for TypedTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match *self {
+            TypedTransaction::Legacy(ref transaction) => {
+                transaction.rlp_append(s);
+            },
+            TypedTransaction::AccessList(_) | TypedTransaction::DynamicFee(_) |
+            TypedTransaction::Blob(_) => {
+                s.append(&self.envelope());
+            },
+        }
+    }
+}
+
+impl Decodable for TypedTransaction {
+    fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+        // In a block body, legacy transactions are RLP lists, while
+        // typed transactions are byte strings of their envelopes.
+        if rlp.is_list() {
+            Ok(TypedTransaction::Legacy(rlp.as_val()?))
+        } else {
+            Self::from_envelope(rlp.data()?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use block::UnsignedTransaction;
+    use secp256k1::key::SecretKey;
+    use hexutil::read_hex;
+
+    fn secret_key() -> SecretKey {
+        SecretKey::from_slice(&SECP256K1, &[1u8; 32]).unwrap()
+    }
+
+    /// Address of the secret key above.
+    fn caller() -> Address {
+        Address::from_str("0x1a642f0e3c3af545e7acbd38b07251b3990914f1").unwrap()
+    }
+
+    fn sign(signing_hash: H256) -> TypedTransactionSignature {
+        let message = Message::from_slice(&signing_hash).unwrap();
+        let (recid, sig) = SECP256K1.sign_recoverable(&message, &secret_key()).unwrap()
+            .serialize_compact(&SECP256K1);
+        TypedTransactionSignature {
+            odd_y_parity: recid.to_i32() == 1,
+            r: U256::from(&sig[0..32]),
+            s: U256::from(&sig[32..64]),
+        }
+    }
+
+    #[test]
+    fn access_list_transaction() {
+        let mut transaction = AccessListTransaction {
+            chain_id: 1,
+            nonce: U256::from(3u64),
+            gas_price: Gas::from(1000u64),
+            gas_limit: Gas::from(50000u64),
+            action: TransactionAction::Call(Address::default()),
+            value: U256::from(5u64),
+            input: vec![1, 2, 3],
+            access_list: vec![AccessListItem {
+                address: Address::default(),
+                storage_keys: vec![H256::from(1u64), H256::from(2u64)],
+            }],
+            signature: TypedTransactionSignature {
+                odd_y_parity: false,
+                r: U256::zero(),
+                s: U256::zero(),
+            },
+        };
+        transaction.signature = sign(transaction.signing_hash());
+        let transaction = TypedTransaction::AccessList(transaction);
+        assert_eq!(transaction.caller().unwrap(), caller());
+
+        let envelope = transaction.envelope();
+        assert_eq!(envelope[0], ACCESS_LIST_TRANSACTION_TYPE);
+        assert_eq!(TypedTransaction::from_envelope(&envelope).unwrap(), transaction);
+        assert_eq!(rlp::decode::<TypedTransaction>(&rlp::encode(&transaction)), transaction);
+
+        let legacy = UnsignedTransaction {
+            nonce: U256::zero(),
+            gas_price: Gas::zero(),
+            gas_limit: Gas::zero(),
+            action: TransactionAction::Create,
+            value: U256::zero(),
+            input: Vec::new(),
+        }.sign_global(&secret_key());
+        let legacy = TypedTransaction::Legacy(legacy);
+        assert_eq!(legacy.caller().unwrap(), caller());
+        assert_eq!(legacy.transaction_type(), 0);
+        assert_eq!(TypedTransaction::from_envelope(&legacy.envelope()).unwrap(), legacy);
+        assert!(TypedTransaction::from_envelope(&[0x7f]).is_err());
+    }
+
+    #[test]
+    fn dynamic_fee_transaction() {
+        let mut transaction = DynamicFeeTransaction {
+            chain_id: 1,
+            nonce: U256::from(3u64),
+            max_priority_fee_per_gas: Gas::from(2u64),
+            max_fee_per_gas: Gas::from(10u64),
+            gas_limit: Gas::from(21000u64),
+            action: TransactionAction::Call(Address::from([0x11u8; 20])),
+            value: U256::from(5u64),
+            input: vec![1, 2, 3],
+            access_list: Vec::new(),
+            signature: TypedTransactionSignature {
+                odd_y_parity: true,
+                r: U256::from(1u64),
+                s: U256::from(2u64),
+            },
+        };
+        // 0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+        // max_fee_per_gas, gas_limit, to, value, data, access_list])
+        let payload = read_hex("0x02e20103020a82520894111111111111111111111111111111111111111105\
+                                83010203c0").unwrap();
+        assert_eq!(transaction.signing_hash(), H256::from(Keccak256::digest(&payload).as_slice()));
+
+        transaction.signature = sign(transaction.signing_hash());
+        let transaction = TypedTransaction::DynamicFee(transaction);
+        assert_eq!(transaction.caller().unwrap(), caller());
+        let envelope = transaction.envelope();
+        assert_eq!(envelope[0], DYNAMIC_FEE_TRANSACTION_TYPE);
+        assert_eq!(TypedTransaction::from_envelope(&envelope).unwrap(), transaction);
+        assert_eq!(rlp::decode::<TypedTransaction>(&rlp::encode(&transaction)), transaction);
+    }
+
+    #[test]
+    fn blob_transaction() {
+        let mut versioned_hash = H256::default();
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        let mut transaction = BlobTransaction {
+            chain_id: 1,
+            nonce: U256::from(3u64),
+            max_priority_fee_per_gas: Gas::from(2u64),
+            max_fee_per_gas: Gas::from(10u64),
+            gas_limit: Gas::from(21000u64),
+            address: Address::from([0x11u8; 20]),
+            value: U256::from(5u64),
+            input: vec![1, 2, 3],
+            access_list: Vec::new(),
+            max_fee_per_blob_gas: Gas::from(7u64),
+            blob_versioned_hashes: vec![versioned_hash],
+            signature: TypedTransactionSignature {
+                odd_y_parity: false,
+                r: U256::from(1u64),
+                s: U256::from(2u64),
+            },
+        };
+        // 0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+        // max_fee_per_gas, gas_limit, to, value, data, access_list,
+        // max_fee_per_blob_gas, blob_versioned_hashes])
+        let payload = read_hex("0x03f8450103020a8252089411111111111111111111111111111111111111\
+                                110583010203c007e1a001000000000000000000000000000000000000000000\
+                                00000000000000000000").unwrap();
+        assert_eq!(transaction.signing_hash(), H256::from(Keccak256::digest(&payload).as_slice()));
+
+        transaction.signature = sign(transaction.signing_hash());
+        let transaction = TypedTransaction::Blob(transaction);
+        assert_eq!(transaction.caller().unwrap(), caller());
+        let envelope = transaction.envelope();
+        assert_eq!(envelope[0], BLOB_TRANSACTION_TYPE);
+        assert_eq!(TypedTransaction::from_envelope(&envelope).unwrap(), transaction);
+        assert_eq!(rlp::decode::<TypedTransaction>(&rlp::encode(&transaction)), transaction);
+    }
+
+    #[test]
+    fn signature_scalars() {
+        let mut transaction = AccessListTransaction {
+            chain_id: 1,
+            nonce: U256::zero(),
+            gas_price: Gas::zero(),
+            gas_limit: Gas::zero(),
+            action: TransactionAction::Create,
+            value: U256::zero(),
+            input: Vec::new(),
+            access_list: Vec::new(),
+            signature: TypedTransactionSignature {
+                odd_y_parity: true,
+                r: U256::from(0x1234u64),
+                s: U256::from(1u64),
+            },
+        };
+
+        let envelope = TypedTransaction::AccessList(transaction.clone()).envelope();
+        assert_eq!(&envelope[(envelope.len() - 5)..], &[0x01, 0x82, 0x12, 0x34, 0x01]);
+        assert_eq!(TypedTransaction::from_envelope(&envelope).unwrap(),
+                   TypedTransaction::AccessList(transaction.clone()));
+
+        transaction.signature.r = U256::zero();
+        assert_eq!(transaction.caller(), Err(Error::InvalidSignature));
+
+        transaction.signature.r = U256::one();
+        transaction.signature.s = U256::from_str(
+            "0x7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a1").unwrap();
+        assert_eq!(transaction.caller(), Err(Error::InvalidSignature));
+    }
+}
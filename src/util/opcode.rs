@@ -7,25 +7,26 @@ pub enum Opcode {
     STOP, ADD, MUL, SUB, DIV, SDIV, MOD, SMOD, ADDMOD, MULMOD, EXP,
     SIGNEXTEND,
 
-    LT, GT, SLT, SGT, EQ, ISZERO, AND, OR, XOR, NOT, BYTE,
+    LT, GT, SLT, SGT, EQ, ISZERO, AND, OR, XOR, NOT, BYTE, SHL, SHR, SAR,
 
     SHA3,
 
     ADDRESS, BALANCE, ORIGIN, CALLER, CALLVALUE, CALLDATALOAD,
     CALLDATASIZE, CALLDATACOPY, CODESIZE, CODECOPY, GASPRICE,
-    EXTCODESIZE, EXTCODECOPY,
+    EXTCODESIZE, EXTCODECOPY, RETURNDATASIZE, RETURNDATACOPY, EXTCODEHASH,
 
     BLOCKHASH, COINBASE, TIMESTAMP, NUMBER, DIFFICULTY, GASLIMIT,
+    CHAINID, SELFBALANCE, BASEFEE, BLOBHASH, BLOBBASEFEE,
 
     POP, MLOAD, MSTORE, MSTORE8, SLOAD, SSTORE, JUMP, JUMPI, PC,
-    MSIZE, GAS, JUMPDEST,
+    MSIZE, GAS, JUMPDEST, TLOAD, TSTORE, MCOPY, PUSH0,
 
     PUSH(usize),
     DUP(usize),
     SWAP(usize),
     LOG(usize),
 
-    CREATE, CALL, CALLCODE, RETURN, DELEGATECALL,
+    CREATE, CALL, CALLCODE, RETURN, DELEGATECALL, CREATE2, STATICCALL, REVERT,
 
     INVALID, SUICIDE
 }
@@ -57,6 +58,9 @@ impl From<u8> for Opcode {
             0x18 => Opcode::XOR,
             0x19 => Opcode::NOT,
             0x1a => Opcode::BYTE,
+            0x1b => Opcode::SHL,
+            0x1c => Opcode::SHR,
+            0x1d => Opcode::SAR,
 
             0x20 => Opcode::SHA3,
 
@@ -73,6 +77,9 @@ impl From<u8> for Opcode {
             0x3a => Opcode::GASPRICE,
             0x3b => Opcode::EXTCODESIZE,
             0x3c => Opcode::EXTCODECOPY,
+            0x3d => Opcode::RETURNDATASIZE,
+            0x3e => Opcode::RETURNDATACOPY,
+            0x3f => Opcode::EXTCODEHASH,
 
             0x40 => Opcode::BLOCKHASH,
             0x41 => Opcode::COINBASE,
@@ -80,6 +87,11 @@ impl From<u8> for Opcode {
             0x43 => Opcode::NUMBER,
             0x44 => Opcode::DIFFICULTY,
             0x45 => Opcode::GASLIMIT,
+            0x46 => Opcode::CHAINID,
+            0x47 => Opcode::SELFBALANCE,
+            0x48 => Opcode::BASEFEE,
+            0x49 => Opcode::BLOBHASH,
+            0x4a => Opcode::BLOBBASEFEE,
 
             0x50 => Opcode::POP,
             0x51 => Opcode::MLOAD,
@@ -93,6 +105,10 @@ impl From<u8> for Opcode {
             0x59 => Opcode::MSIZE,
             0x5a => Opcode::GAS,
             0x5b => Opcode::JUMPDEST,
+            0x5c => Opcode::TLOAD,
+            0x5d => Opcode::TSTORE,
+            0x5e => Opcode::MCOPY,
+            0x5f => Opcode::PUSH0,
 
             0x60 => Opcode::PUSH(1),
             0x61 => Opcode::PUSH(2),
@@ -172,6 +188,9 @@ impl From<u8> for Opcode {
             0xf2 => Opcode::CALLCODE,
             0xf3 => Opcode::RETURN,
             0xf4 => Opcode::DELEGATECALL,
+            0xf5 => Opcode::CREATE2,
+            0xfa => Opcode::STATICCALL,
+            0xfd => Opcode::REVERT,
 
             0xff => Opcode::SUICIDE,
             _ => Opcode::INVALID,
@@ -206,6 +225,9 @@ impl Into<u8> for Opcode {
             Opcode::XOR => 0x18,
             Opcode::NOT => 0x19,
             Opcode::BYTE => 0x1a,
+            Opcode::SHL => 0x1b,
+            Opcode::SHR => 0x1c,
+            Opcode::SAR => 0x1d,
 
             Opcode::SHA3 => 0x20,
 
@@ -222,6 +244,9 @@ impl Into<u8> for Opcode {
             Opcode::GASPRICE => 0x3a,
             Opcode::EXTCODESIZE => 0x3b,
             Opcode::EXTCODECOPY => 0x3c,
+            Opcode::RETURNDATASIZE => 0x3d,
+            Opcode::RETURNDATACOPY => 0x3e,
+            Opcode::EXTCODEHASH => 0x3f,
 
             Opcode::BLOCKHASH => 0x40,
             Opcode::COINBASE => 0x41,
@@ -229,6 +254,11 @@ impl Into<u8> for Opcode {
             Opcode::NUMBER => 0x43,
             Opcode::DIFFICULTY => 0x44,
             Opcode::GASLIMIT => 0x45,
+            Opcode::CHAINID => 0x46,
+            Opcode::SELFBALANCE => 0x47,
+            Opcode::BASEFEE => 0x48,
+            Opcode::BLOBHASH => 0x49,
+            Opcode::BLOBBASEFEE => 0x4a,
 
             Opcode::POP => 0x50,
             Opcode::MLOAD => 0x51,
@@ -242,6 +272,10 @@ impl Into<u8> for Opcode {
             Opcode::MSIZE => 0x59,
             Opcode::GAS => 0x5a,
             Opcode::JUMPDEST => 0x5b,
+            Opcode::TLOAD => 0x5c,
+            Opcode::TSTORE => 0x5d,
+            Opcode::MCOPY => 0x5e,
+            Opcode::PUSH0 => 0x5f,
 
             Opcode::PUSH(v) => {
                 assert!(v >= 1 && v <= 32);
@@ -268,6 +302,9 @@ impl Into<u8> for Opcode {
             Opcode::CALLCODE => 0xf2,
             Opcode::RETURN => 0xf3,
             Opcode::DELEGATECALL => 0xf4,
+            Opcode::CREATE2 => 0xf5,
+            Opcode::STATICCALL => 0xfa,
+            Opcode::REVERT => 0xfd,
 
             Opcode::INVALID => 0xfe,
             Opcode::SUICIDE => 0xff,
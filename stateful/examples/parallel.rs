@@ -35,6 +35,8 @@ pub fn parallel_execute(
         number: U256::zero(),
         difficulty: U256::zero(),
         gas_limit: Gas::max_value(),
+        base_fee: Gas::zero(),
+        excess_blob_gas: Gas::zero(),
     };
 
     let stateful = Arc::new(stateful);
@@ -132,6 +134,10 @@ fn main() {
             value: U256::from_str("0x1000").unwrap(),
             input: Vec::new(),
             nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
         },
         ValidTransaction {
             caller: Some(addr2),
@@ -141,6 +147,10 @@ fn main() {
             value: U256::from_str("0x1000").unwrap(),
             input: Vec::new(),
             nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
         },
         ValidTransaction {
             caller: Some(addr3),
@@ -150,6 +160,10 @@ fn main() {
             value: U256::from_str("0x1000").unwrap(),
             input: Vec::new(),
             nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
         },
     ]);
 
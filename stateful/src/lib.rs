@@ -380,13 +380,29 @@ impl<'b, D: DatabaseOwned> Stateful<'b, D> {
 
     pub fn to_valid<P: Patch>(
         &self, transaction: Transaction,
+    ) -> Result<ValidTransaction, PreExecutionError> {
+        self.to_valid_with(|account_state| {
+            ValidTransaction::from_transaction::<P>(&transaction, account_state)
+        })
+    }
+
+    pub fn to_valid_in_block<P: Patch>(
+        &self, transaction: Transaction, block: &HeaderParams,
+    ) -> Result<ValidTransaction, PreExecutionError> {
+        self.to_valid_with(|account_state| {
+            ValidTransaction::from_transaction_in_block::<P>(&transaction, block, account_state)
+        })
+    }
+
+    fn to_valid_with<F: Fn(&AccountState) -> Result<Result<ValidTransaction, PreExecutionError>, RequireError>>(
+        &self, validate: F,
     ) -> Result<ValidTransaction, PreExecutionError> {
         let state = self.database.create_fixed_secure_trie(self.root);
         let code_hashes = self.database.create_guard();
         let mut account_state = AccountState::default();
 
         loop {
-            match ValidTransaction::from_transaction::<P>(&transaction, &account_state) {
+            match validate(&account_state) {
                 Ok(val) => return val,
                 Err(RequireError::Account(address)) => {
                     let account: Option<Account> = state.get(&address);
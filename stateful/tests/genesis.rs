@@ -99,12 +99,18 @@ fn genesis_state_root() {
             value: balance,
             input: Vec::new(),
             nonce: U256::zero(),
+            access_list: Vec::new(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
         }, HeaderParams {
             beneficiary: Address::default(),
             timestamp: 0,
             number: U256::zero(),
             difficulty: U256::zero(),
-            gas_limit: Gas::max_value()
+            gas_limit: Gas::max_value(),
+            base_fee: Gas::zero(),
+            excess_blob_gas: Gas::zero(),
         }, &[]);
         match vm.status() {
             VMStatus::ExitedOk => (),